@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use dioxus::prelude::*;
+
+use crate::logic::layout::{Position, force_layout};
+use crate::logic::model::{Assignment, PersonName, RelationStrength, Tribe};
+
+const LAYOUT_ITERATIONS: usize = 300;
+/// Size of the drawing in SVG units, the layout being in the unit square.
+const SIZE: f32 = 600.0;
+
+fn strength_stroke(strength: RelationStrength) -> &'static str {
+    match strength {
+        RelationStrength::Hates => "stroke-error",
+        RelationStrength::Dislikes => "stroke-warning",
+        RelationStrength::Likes => "stroke-info",
+        RelationStrength::Loves => "stroke-success",
+    }
+}
+
+/// The tribe as a graph, with persons placed by their relations.
+///
+/// Clicking a person highlights their relations, and persons can be grouped by the table they
+/// are seated at when a non-empty assignment is given.
+#[component]
+pub fn RelationGraph(
+    tribe: Signal<Tribe>,
+    #[props(default)] assignment: Option<Signal<Assignment>>,
+) -> Element {
+    let mut selected = use_signal(|| None::<PersonName>);
+    let mut by_table = use_signal(|| false);
+
+    let layout = use_memo(move || {
+        let assignment = assignment.filter(|_| by_table()).map(|a| a.read().clone());
+        force_layout(&tribe.read(), assignment.as_ref(), LAYOUT_ITERATIONS)
+    });
+    let table_labels = use_memo(move || match (assignment, by_table()) {
+        (Some(assignment), true) => table_centers(&assignment.read(), &layout.read()),
+        _ => Vec::new(),
+    });
+
+    let point = move |name: &PersonName| {
+        layout
+            .read()
+            .get(name)
+            .map(|(x, y)| (x * SIZE, y * SIZE))
+            .unwrap_or_default()
+    };
+    let is_selected = move |person: &PersonName| selected.read().as_ref() == Some(person);
+    let is_unrelated = move |person: &PersonName| match &*selected.read() {
+        Some(s) => s != person && tribe.read().relation(s, person).is_none(),
+        None => false,
+    };
+    let is_faded = move |p1: &PersonName, p2: &PersonName| match &*selected.read() {
+        Some(s) => s != p1 && s != p2,
+        None => false,
+    };
+
+    rsx! {
+        if assignment.is_some_and(|a| !a.read().is_empty()) {
+            label { class: "label my-2",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-sm",
+                    checked: by_table(),
+                    onchange: move |event| by_table.set(event.checked()),
+                }
+                "Group by table"
+            }
+        }
+        svg {
+            class: "w-full h-auto select-none",
+            view_box: "0 0 {SIZE} {SIZE}",
+            onclick: move |_| selected.set(None),
+            for (table , (x , y)) in table_labels.read().iter() {
+                text {
+                    class: "fill-base-content/30 text-4xl font-bold",
+                    x: x * SIZE,
+                    y: y * SIZE,
+                    text_anchor: "middle",
+                    "{table}"
+                }
+            }
+            for (p1 , p2 , strength) in tribe.read().relations() {
+                line {
+                    class: format!(
+                        "{} {}",
+                        strength_stroke(strength),
+                        if is_faded(p1, p2) { "opacity-10" } else { "" },
+                    ),
+                    stroke_width: 2,
+                    x1: point(p1).0,
+                    y1: point(p1).1,
+                    x2: point(p2).0,
+                    y2: point(p2).1,
+                }
+            }
+            for person in tribe.read().persons().cloned() {
+                g {
+                    class: "cursor-pointer",
+                    opacity: if is_unrelated(&person) { "0.3" } else { "1" },
+                    onclick: {
+                        let person = person.clone();
+                        move |event: MouseEvent| {
+                            event.stop_propagation();
+                            let clicked = Some(person.clone());
+                            selected.set(if *selected.peek() == clicked { None } else { clicked });
+                        }
+                    },
+                    circle {
+                        class: if is_selected(&person) { "fill-primary" } else { "fill-neutral" },
+                        cx: point(&person).0,
+                        cy: point(&person).1,
+                        r: 7,
+                    }
+                    text {
+                        class: "fill-base-content text-xs",
+                        x: point(&person).0,
+                        y: point(&person).1 - 10.0,
+                        text_anchor: "middle",
+                        "{person}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The mean position of the persons at every table.
+fn table_centers(
+    assignment: &Assignment,
+    layout: &BTreeMap<PersonName, Position>,
+) -> Vec<(String, Position)> {
+    assignment
+        .iter()
+        .filter_map(|(table, persons)| {
+            let points = persons
+                .iter()
+                .filter_map(|p| layout.get(p))
+                .collect::<Vec<_>>();
+            let n = points.len() as f32;
+            (!points.is_empty()).then(|| {
+                let x = points.iter().map(|p| p.0).sum::<f32>() / n;
+                let y = points.iter().map(|p| p.1).sum::<f32>() / n;
+                (table.clone(), (x, y))
+            })
+        })
+        .collect()
+}
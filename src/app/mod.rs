@@ -1,3 +1,4 @@
+pub mod graph;
 pub mod problem;
 pub mod solution;
 pub mod ui;
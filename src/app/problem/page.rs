@@ -1,8 +1,17 @@
+use std::collections::BTreeSet;
+
 use dioxus::prelude::*;
 use dioxus_free_icons::{Icon, icons::ld_icons as icons};
 
-use crate::app::ui::{Card, UnassignedSchema};
-use crate::logic::model::{RelationStrength, TableType, Tables, Tribe};
+use crate::app::graph::RelationGraph;
+use crate::app::ui::{Card, UnassignedSchema, download};
+use crate::logic::model::{
+    Attribute, AttributeGoal, GroupCohesion, PersonName, RelationStrength, Rsvp, TableType, Tables,
+    Tribe,
+};
+use crate::logic::solver::SolverSettings;
+use crate::logic::{export, import};
+use crate::name_generator::{NameGenerator, Theme};
 
 #[component]
 pub fn Page() -> Element {
@@ -10,48 +19,100 @@ pub fn Page() -> Element {
 
     rsx! {
         UnassignedSchema { tables: pb.tables, tribe: pb.tribe }
+        HistoryButtons { class: "fixed bottom-4 left-4 z-50", history: pb.history }
         ShowMeHowButton {
             class: "fixed bottom-4 right-4 z-50",
-            tables: pb.tables,
-            tribe: pb.tribe,
+            history: pb.history,
         }
-        div { class: "space-y-2 p-2 pb-8 lg:px-8  lg:gap-4 lg:flex",
-            div { class: "lg:basis-1/3",
+        div { class: "space-y-2 p-2 pb-8 lg:px-8 lg:gap-4 lg:space-y-0 lg:grid lg:grid-cols-3",
+            div {
                 Card {
                     header: rsx! {
                         div { class: "w-full flex justify-between",
                             h2 { "Tables" }
-                            TableInput { tables: pb.tables }
+                            TableInput { tables: pb.tables, history: pb.history }
                         }
                     },
                     body: rsx! {
-                        TableList { tables: pb.tables }
+                        TableList { tables: pb.tables, history: pb.history }
+                        TableOptions { settings: pb.settings, history: pb.history }
                     },
                 }
             }
-            div { class: "lg:basis-1/3",
+            div {
                 Card {
                     header: rsx! {
                         div { class: "w-full flex justify-between",
                             h2 { "Persons" }
-                            PersonInput { tribe: pb.tribe }
+                            PersonInput { tribe: pb.tribe, history: pb.history }
                         }
                     },
                     body: rsx! {
-                        PersonList { tribe: pb.tribe }
+                        RsvpSummary {
+                            tribe: pb.tribe,
+                            settings: pb.settings,
+                            history: pb.history,
+                        }
+                        PersonList { tribe: pb.tribe, history: pb.history }
                     },
                 }
             }
-            div { class: "lg:basis-1/3",
+            div {
                 Card {
                     header: rsx! {
                         div { class: "w-full flex justify-between",
                             h2 { "Relations" }
-                            RelationInput { tribe: pb.tribe }
+                            div { class: "flex gap-2",
+                                RelationExport { tribe: pb.tribe }
+                                RelationInput { tribe: pb.tribe, history: pb.history }
+                            }
+                        }
+                    },
+                    body: rsx! {
+                        RelationViews { tribe: pb.tribe, history: pb.history }
+                    },
+                }
+            }
+            div {
+                Card {
+                    header: rsx! {
+                        div { class: "w-full flex justify-between",
+                            h2 { "Groups" }
+                            GroupInput { tribe: pb.tribe, history: pb.history }
+                        }
+                    },
+                    body: rsx! {
+                        GroupList { tribe: pb.tribe, history: pb.history }
+                    },
+                }
+            }
+            div {
+                Card {
+                    header: rsx! {
+                        div { class: "w-full flex justify-between",
+                            h2 { "Attributes" }
+                            AttributeInput { tribe: pb.tribe, history: pb.history }
                         }
                     },
                     body: rsx! {
-                        RelationList { tribe: pb.tribe }
+                        AttributeList { tribe: pb.tribe, history: pb.history }
+                    },
+                }
+            }
+            div {
+                Card {
+                    header: rsx! {
+                        div { class: "w-full flex justify-between",
+                            h2 { "Table goals" }
+                            GoalInput {
+                                tribe: pb.tribe,
+                                settings: pb.settings,
+                                history: pb.history,
+                            }
+                        }
+                    },
+                    body: rsx! {
+                        GoalList { settings: pb.settings, history: pb.history }
                     },
                 }
             }
@@ -72,15 +133,14 @@ fn TableAddIcon() -> Element {
 }
 
 #[component]
-fn ShowMeHowButton(tribe: Signal<Tribe>, tables: Signal<Tables>, class: &'static str) -> Element {
+fn ShowMeHowButton(history: crate::ProblemHistory, class: &'static str) -> Element {
     rsx! {
         button {
             class: format!("btn btn-primary {}", class),
             onclick: {
                 move |_| {
                     let (ex_tribe, ex_tables) = crate::logic::examples::harry_potter();
-                    tribe.set(ex_tribe);
-                    tables.set(ex_tables);
+                    history.replace(ex_tables, ex_tribe);
                 }
             },
             "Show me how!"
@@ -88,6 +148,28 @@ fn ShowMeHowButton(tribe: Signal<Tribe>, tables: Signal<Tables>, class: &'static
     }
 }
 
+#[component]
+fn HistoryButtons(history: crate::ProblemHistory, class: &'static str) -> Element {
+    rsx! {
+        div { class: format!("join shadow {}", class),
+            button {
+                class: "btn join-item",
+                title: "Undo (Ctrl+Z)",
+                disabled: !history.can_undo(),
+                onclick: move |_| history.undo(),
+                Icon { icon: icons::LdUndo2 }
+            }
+            button {
+                class: "btn join-item",
+                title: "Redo (Ctrl+Shift+Z)",
+                disabled: !history.can_redo(),
+                onclick: move |_| history.redo(),
+                Icon { icon: icons::LdRedo2 }
+            }
+        }
+    }
+}
+
 #[component]
 fn SectionTrashButton(onclick: EventHandler<MouseEvent>) -> Element {
     rsx! {
@@ -154,24 +236,37 @@ fn SectionAdd(title: String, children: Element) -> Element {
 }
 
 #[component]
-fn PersonList(tribe: Signal<Tribe>) -> Element {
+fn PersonList(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    let mut to_delete = use_signal(|| None::<PersonName>);
+
     rsx! {
         table { class: "table",
             thead {
                 th { "Name" }
+                th { "RSVP" }
+                th { class: "w-4" }
                 th { class: "w-4" }
             }
             tbody {
                 for person in tribe.read().persons() {
-                    tr {
-                        td { "{person}" }
+                    tr { class: if tribe.read().is_absent(person) { "opacity-50" },
+                        td {
+                            "{person}"
+                            if tribe.read().is_absent(person) {
+                                span { class: "badge badge-ghost badge-sm ml-2", "Absent" }
+                            }
+                        }
+                        td {
+                            RsvpSelect { person: person.to_owned(), tribe, history }
+                        }
+                        td {
+                            AbsentButton { person: person.to_owned(), tribe, history }
+                        }
                         td {
                             SectionTrashButton {
                                 onclick: {
                                     let person = person.to_owned();
-                                    move |_| {
-                                        tribe.write().remove_person(&person);
-                                    }
+                                    move |_| to_delete.set(Some(person.clone()))
                                 },
                             }
                         }
@@ -179,16 +274,162 @@ fn PersonList(tribe: Signal<Tribe>) -> Element {
                 }
             }
         }
+        if let Some(person) = to_delete() {
+            PersonDeleteDialog {
+                person,
+                tribe,
+                history,
+                onclose: move |_| to_delete.set(None),
+            }
+        }
     }
 }
 
 #[component]
-fn PersonInput(tribe: Signal<Tribe>) -> Element {
+fn RsvpSummary(
+    tribe: Signal<Tribe>,
+    settings: Signal<SolverSettings>,
+    history: crate::ProblemHistory,
+) -> Element {
+    rsx! {
+        div { class: "flex flex-wrap gap-2 items-center",
+            for rsvp in Rsvp::iter() {
+                span { class: rsvp_badge_class(rsvp),
+                    "{tribe.read().rsvp_count(rsvp)} {rsvp}"
+                }
+            }
+            label { class: "label ml-auto",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-sm",
+                    checked: settings.read().include_tentative,
+                    onchange: move |event| {
+                        history.edit_settings(|settings| settings.include_tentative = event.checked());
+                    },
+                }
+                "Seat tentative guests"
+            }
+        }
+    }
+}
+
+fn rsvp_badge_class(rsvp: Rsvp) -> &'static str {
+    match rsvp {
+        Rsvp::Confirmed => "badge badge-success",
+        Rsvp::Tentative => "badge badge-warning",
+        Rsvp::Declined => "badge badge-error",
+    }
+}
+
+#[component]
+fn RsvpSelect(person: PersonName, tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    let current = tribe.read().rsvp(&person);
+
+    rsx! {
+        select {
+            class: "select select-xs w-28",
+            onchange: move |event| {
+                if let Ok(rsvp) = event.value().parse::<Rsvp>() {
+                    history.edit_tribe(|tribe| tribe.set_rsvp(&person, rsvp));
+                }
+            },
+            for rsvp in Rsvp::iter() {
+                option { value: "{rsvp}", selected: rsvp == current, "{rsvp}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn AbsentButton(
+    person: PersonName,
+    tribe: Signal<Tribe>,
+    history: crate::ProblemHistory,
+) -> Element {
+    let absent = tribe.read().is_absent(&person);
+
+    rsx! {
+        button {
+            class: "btn btn-xs aspect-square p-0",
+            title: if absent { "Mark as present" } else { "Mark as absent" },
+            onclick: move |_| history.edit_tribe(|tribe| tribe.set_absent(&person, !absent)),
+            if absent {
+                Icon { width: 15, height: 15, icon: icons::LdUserCheck }
+            } else {
+                Icon { width: 15, height: 15, icon: icons::LdUserX }
+            }
+        }
+    }
+}
+
+/// Confirm deleting a person, showing what would be lost with them.
+#[component]
+fn PersonDeleteDialog(
+    person: PersonName,
+    tribe: Signal<Tribe>,
+    history: crate::ProblemHistory,
+    onclose: EventHandler<()>,
+) -> Element {
+    let relations = tribe
+        .read()
+        .person_relations(&person)
+        .map(|(p1, p2, strength)| format!("{p1} {strength} {p2}"))
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "modal modal-open",
+            div { class: "modal-box space-y-4",
+                h3 { class: "card-title", "Delete {person}?" }
+                if relations.is_empty() {
+                    p { "{person} has no relations." }
+                } else {
+                    p { "The following relations will also be deleted:" }
+                    ul { class: "list-disc list-inside",
+                        for relation in relations.iter() {
+                            li { "{relation}" }
+                        }
+                    }
+                }
+                p { class: "text-sm opacity-70",
+                    "Marking them as absent instead keeps their relations but does not seat them."
+                }
+                div { class: "modal-action",
+                    button { class: "btn", onclick: move |_| onclose.call(()), "Cancel" }
+                    button {
+                        class: "btn btn-secondary",
+                        onclick: {
+                            let person = person.clone();
+                            move |_| {
+                                history.edit_tribe(|tribe| tribe.set_absent(&person, true));
+                                onclose.call(());
+                            }
+                        },
+                        "Mark as absent"
+                    }
+                    button {
+                        class: "btn btn-error",
+                        onclick: move |_| {
+                            history.edit_tribe(|tribe| tribe.remove_person(&person));
+                            onclose.call(());
+                        },
+                        "Delete"
+                    }
+                }
+            }
+            div { class: "modal-backdrop", onclick: move |_| onclose.call(()) }
+        }
+    }
+}
+
+#[component]
+fn PersonInput(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
     const SPLIT_CHARS: [char; 3] = [',', ';', '\n'];
+    const PERSON_GROUP_ID: &str = "person_group";
 
     let mut persons = use_signal(Vec::<String>::new);
     let mut current = use_signal(String::new);
     let mut input_key = use_signal(|| 0);
+    let mut group = use_signal(String::new);
 
     let parse_input = move |event: Event<FormData>| {
         let mut remaining = event.value();
@@ -218,8 +459,6 @@ fn PersonInput(tribe: Signal<Tribe>) -> Element {
 
     rsx! {
         SectionAdd { title: "Add a new person",
-            // TODO: add "group" for auto conflicts
-            // https://developer.mozilla.org/en-US/docs/Web/HTML/Element/datalist
             form { class: "mx-auto space-y-2",
                 fieldset { class: "fieldset",
                     label { class: "floating-label input focus-within:outline-none w-full flex-wrap h-auto py-2",
@@ -263,6 +502,23 @@ fn PersonInput(tribe: Signal<Tribe>) -> Element {
                     }
                 }
 
+                label { r#for: PERSON_GROUP_ID, class: "floating-label",
+                    select {
+                        id: PERSON_GROUP_ID,
+                        class: "select focus:outline-none w-full",
+                        onchange: move |event| group.set(event.value()),
+                        option { value: "", selected: group.read().is_empty(), "No group" }
+                        for name in tribe.read().groups().map(|(name, _)| name) {
+                            option {
+                                value: "{name}",
+                                selected: *name == *group.read(),
+                                "{name}"
+                            }
+                        }
+                    }
+                    span { "Group" }
+                }
+
                 div { class: "divider", "OR" }
 
                 button {
@@ -272,13 +528,18 @@ fn PersonInput(tribe: Signal<Tribe>) -> Element {
                         let mut persons = persons;
                         let mut current = current;
                         move |_| {
-                            std::mem::take(&mut *persons.write())
-                                .into_iter()
-                                .for_each(|p| tribe.write().add_person(p));
-                            let maybe_person = std::mem::take(&mut *current.write());
-                            if !maybe_person.is_empty() {
-                                tribe.write().add_person(maybe_person);
-                            }
+                            let mut new_persons = std::mem::take(&mut *persons.write());
+                            new_persons.push(std::mem::take(&mut *current.write()));
+                            let group = group.read();
+                            history.edit_tribe(|tribe| {
+                                for person in new_persons.into_iter().filter(|p| !p.is_empty()) {
+                                    if group.is_empty() {
+                                        tribe.add_person(person);
+                                    } else {
+                                        tribe.add_to_group(group.as_str(), person);
+                                    }
+                                }
+                            });
                         }
                     },
                     "Add"
@@ -289,7 +550,7 @@ fn PersonInput(tribe: Signal<Tribe>) -> Element {
 }
 
 #[component]
-fn TableList(tables: Signal<Tables>) -> Element {
+fn TableList(tables: Signal<Tables>, history: crate::ProblemHistory) -> Element {
     rsx! {
         table { class: "table",
             thead {
@@ -307,7 +568,9 @@ fn TableList(tables: Signal<Tables>) -> Element {
                                 onclick: {
                                     let name = name.to_owned();
                                     move |_| {
-                                        tables.write().remove(&name);
+                                        history.edit_tables(|tables| {
+                                            tables.remove(&name);
+                                        });
                                     }
                                 },
                             }
@@ -320,9 +583,61 @@ fn TableList(tables: Signal<Tables>) -> Element {
 }
 
 #[component]
-fn TableInput(tables: Signal<Tables>) -> Element {
+fn TableOptions(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        fieldset { class: "fieldset pt-4",
+            legend { class: "fieldset-legend", "Seating options" }
+            label { class: "input focus-within:outline-none w-full",
+                span { class: "label", "Minimum persons per table" }
+                input {
+                    r#type: "number",
+                    min: 0,
+                    step: 1,
+                    value: settings.read().min_table_persons,
+                    onchange: move |event| {
+                        if let Ok(min) = event.value().parse::<u32>() {
+                            history.edit_settings(|settings| settings.min_table_persons = min);
+                        }
+                    },
+                }
+            }
+            label { class: "label",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-sm",
+                    checked: settings.read().close_unused_tables,
+                    onchange: move |event| {
+                        history.edit_settings(|settings| settings.close_unused_tables = event.checked());
+                    },
+                }
+                "Close tables not needed to seat everyone"
+            }
+            label { class: "label", "Balance how full tables are" }
+            input {
+                r#type: "range",
+                min: 0,
+                max: 4,
+                step: 0.5,
+                value: settings.read().fill_balance_value,
+                class: "range range-primary range-xs w-full",
+                onchange: move |event| {
+                    if let Ok(value) = event.value().parse::<f32>() {
+                        history.edit_settings(|settings| settings.fill_balance_value = value);
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn TableInput(tables: Signal<Tables>, history: crate::ProblemHistory) -> Element {
     const TABLE_SEATS_ID: &str = "table_seats";
     const TABLE_NAME_ID: &str = "table_name";
+    const TABLE_NAME_THEME_ID: &str = "table_name_theme";
+
+    let mut names: Signal<NameGenerator> = use_context();
+    let suggested_name = use_memo(move || names.read().peek_free(&tables.read()));
 
     rsx! {
         SectionAdd { title: "Add tables",
@@ -336,7 +651,13 @@ fn TableInput(tables: Signal<Tables>) -> Element {
                         .and_then(|val| val.parse::<u32>().ok());
                     let name_input = data.remove(TABLE_NAME_ID).map(|val| val.as_value());
                     if let Some((n_seats, name)) = n_seats_input.zip(name_input) {
-                        tables.write().insert(name, TableType { n_seats });
+                        // Move the generator past the suggestion once it is used
+                        if name == *suggested_name.peek() {
+                            names.write().next_free(&tables.peek());
+                        }
+                        history.edit_tables(|tables| {
+                            tables.insert(name, TableType { n_seats });
+                        });
                     }
                 },
                 label { r#for: TABLE_NAME_ID, class: "floating-label",
@@ -347,9 +668,29 @@ fn TableInput(tables: Signal<Tables>) -> Element {
                         minlength: 1,
                         class: "input focus:outline-none w-full",
                         placeholder: "Table name",
+                        value: suggested_name,
                     }
                     span { "Table name" }
                 }
+                label { r#for: TABLE_NAME_THEME_ID, class: "floating-label",
+                    select {
+                        id: TABLE_NAME_THEME_ID,
+                        class: "select focus:outline-none w-full",
+                        onchange: move |event| {
+                            if let Ok(theme) = event.value().parse::<Theme>() {
+                                names.set(NameGenerator::with_theme(theme));
+                            }
+                        },
+                        for theme in Theme::iter() {
+                            option {
+                                value: "{theme}",
+                                selected: theme == names.read().theme(),
+                                "{theme}"
+                            }
+                        }
+                    }
+                    span { "Name theme" }
+                }
                 label { r#for: TABLE_SEATS_ID, class: "floating-label",
                     input {
                         id: TABLE_SEATS_ID,
@@ -373,7 +714,7 @@ fn TableInput(tables: Signal<Tables>) -> Element {
 }
 
 #[component]
-fn RelationList(tribe: Signal<Tribe>) -> Element {
+fn RelationList(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
     rsx! {
         table { class: "table",
             thead {
@@ -394,7 +735,7 @@ fn RelationList(tribe: Signal<Tribe>) -> Element {
                                     let p1 = p1.to_owned();
                                     let p2 = p2.to_owned();
                                     move |_| {
-                                        tribe.write().remove_relation(&p1, &p2);
+                                        history.edit_tribe(|tribe| tribe.remove_relation(&p1, &p2));
                                     }
                                 },
                             }
@@ -407,7 +748,225 @@ fn RelationList(tribe: Signal<Tribe>) -> Element {
 }
 
 #[component]
-fn RelationInput(mut tribe: Signal<Tribe>) -> Element {
+fn RelationViews(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    #[derive(Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter)]
+    enum View {
+        List,
+        Matrix,
+        Graph,
+    }
+
+    let solution: crate::SolutionSignal = use_context();
+    let mut view = use_signal(|| View::List);
+
+    rsx! {
+        div { role: "tablist", class: "tabs tabs-border",
+            for v in <View as strum::IntoEnumIterator>::iter() {
+                a {
+                    role: "tab",
+                    class: if view() == v { "tab tab-active" } else { "tab" },
+                    onclick: move |_| view.set(v),
+                    "{v}"
+                }
+            }
+        }
+        match view() {
+            View::List => rsx! {
+                RelationList { tribe, history }
+            },
+            View::Matrix => rsx! {
+                RelationMatrix { tribe, history }
+            },
+            View::Graph => rsx! {
+                RelationGraph { tribe, assignment: solution.assignment }
+            },
+        }
+    }
+}
+
+fn strength_symbol(strength: Option<RelationStrength>) -> &'static str {
+    match strength {
+        None => "",
+        Some(RelationStrength::Hates) => "−−",
+        Some(RelationStrength::Dislikes) => "−",
+        Some(RelationStrength::Likes) => "+",
+        Some(RelationStrength::Loves) => "++",
+    }
+}
+
+fn strength_class(strength: Option<RelationStrength>) -> &'static str {
+    match strength {
+        None => "",
+        Some(RelationStrength::Hates) => "bg-error text-error-content",
+        Some(RelationStrength::Dislikes) => "bg-warning text-warning-content",
+        Some(RelationStrength::Likes) => "bg-info text-info-content",
+        Some(RelationStrength::Loves) => "bg-success text-success-content",
+    }
+}
+
+fn matrix_persons(tribe: &Tribe, filter: &str) -> Vec<PersonName> {
+    let filter = filter.to_lowercase();
+    tribe
+        .persons()
+        .filter(|p| p.to_lowercase().contains(&filter))
+        .cloned()
+        .collect()
+}
+
+/// Every directed relation as a grid cell, clicked or typed on to cycle through strengths.
+#[component]
+fn RelationMatrix(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    let mut filter = use_signal(String::new);
+    let mut cursor = use_signal(|| (0_usize, 0_usize));
+    let persons = use_memo(move || matrix_persons(&tribe.read(), &filter.read()));
+
+    let mut edit = move |row: usize, col: usize, cycle: bool| {
+        let persons = persons.peek();
+        let (Some(p1), Some(p2)) = (persons.get(row), persons.get(col)) else {
+            return;
+        };
+        if p1 == p2 {
+            return;
+        }
+        history.edit_tribe(|tribe| {
+            let next = match cycle {
+                true => RelationStrength::cycle(tribe.directed_relation(p1, p2)),
+                false => None,
+            };
+            tribe.remove_relation(p1, p2);
+            if let Some(strength) = next {
+                tribe.add_relation(p1.clone(), p2.clone(), strength);
+            }
+        });
+    };
+
+    let onkeydown = move |event: KeyboardEvent| {
+        let last = persons.peek().len().saturating_sub(1);
+        let (row, col) = *cursor.peek();
+        match event.key() {
+            Key::ArrowUp => cursor.set((row.saturating_sub(1), col)),
+            Key::ArrowDown => cursor.set(((row + 1).min(last), col)),
+            Key::ArrowLeft => cursor.set((row, col.saturating_sub(1))),
+            Key::ArrowRight => cursor.set((row, (col + 1).min(last))),
+            Key::Enter => edit(row, col, true),
+            Key::Character(c) if c == " " => edit(row, col, true),
+            Key::Delete | Key::Backspace => edit(row, col, false),
+            _ => return,
+        }
+        event.prevent_default();
+    };
+
+    rsx! {
+        label { class: "input input-sm focus-within:outline-none w-full my-2",
+            Icon { class: "size-[1em]", icon: icons::LdSearch }
+            input {
+                r#type: "search",
+                placeholder: "Filter persons",
+                value: filter,
+                oninput: move |event| {
+                    filter.set(event.value());
+                    cursor.set((0, 0));
+                },
+            }
+        }
+        p { class: "label text-xs mb-2",
+            "Rows set relations toward columns. Use arrows to move, Enter to cycle and Delete to clear."
+        }
+        div {
+            class: "overflow-auto max-h-[32rem] focus:outline-primary",
+            tabindex: 0,
+            onkeydown,
+            table { class: "table table-xs table-pin-rows table-pin-cols",
+                thead {
+                    tr {
+                        th {}
+                        for person in persons.read().iter() {
+                            td {
+                                class: "[writing-mode:vertical-rl] rotate-180 font-normal",
+                                title: "{person}",
+                                "{person}"
+                            }
+                        }
+                    }
+                }
+                tbody {
+                    for (row , p1) in persons.read().iter().enumerate() {
+                        tr {
+                            th { class: "font-normal whitespace-nowrap", "{p1}" }
+                            for (col , p2) in persons.read().iter().enumerate() {
+                                if p1 == p2 {
+                                    td { class: "bg-base-300" }
+                                } else {
+                                    td {
+                                        class: format!(
+                                            "cursor-pointer text-center {} {}",
+                                            strength_class(tribe.read().directed_relation(p1, p2)),
+                                            if *cursor.read() == (row, col) { "outline-2 outline-primary" } else { "" },
+                                        ),
+                                        title: "{p1} → {p2}",
+                                        onclick: move |_| {
+                                            cursor.set((row, col));
+                                            edit(row, col, true);
+                                        },
+                                        {strength_symbol(tribe.read().directed_relation(p1, p2))}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Download the relations as a graph file, with tables as clusters once solved.
+#[component]
+fn RelationExport(tribe: Signal<Tribe>) -> Element {
+    let solution: crate::SolutionSignal = use_context();
+    let assignment = move || {
+        let assignment = solution.assignment.read();
+        (!assignment.is_empty()).then(|| assignment.clone())
+    };
+    let seed = move || solution.stats.read().as_ref().map(|stats| stats.seed);
+
+    rsx! {
+        div { class: "dropdown dropdown-end",
+            div {
+                tabindex: 0,
+                role: "button",
+                class: "btn btn-sm aspect-square p-0",
+                title: "Export",
+                Icon { width: 20, height: 20, icon: icons::LdDownload }
+            }
+            ul {
+                tabindex: 0,
+                class: "dropdown-content menu bg-base-100 rounded-box z-10 w-40 p-2 shadow-sm text-sm font-normal",
+                li {
+                    a {
+                        onclick: move |_| {
+                            let dot = export::to_dot(&tribe.read(), assignment().as_ref(), seed());
+                            download("tribe.dot", "text/vnd.graphviz", dot);
+                        },
+                        "DOT (Graphviz)"
+                    }
+                }
+                li {
+                    a {
+                        onclick: move |_| {
+                            let graphml = export::to_graphml(&tribe.read(), assignment().as_ref(), seed());
+                            download("tribe.graphml", "application/xml", graphml);
+                        },
+                        "GraphML (Gephi)"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RelationInput(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
     const RELATION_STRENGTH_ID: &str = "relation_strength";
     const RELATION_STRENGTH_DATALIST_ID: &str = "relation_strength_datalist";
     const RELATION_PERSON_1_ID: &str = "relation_person_1";
@@ -429,7 +988,7 @@ fn RelationInput(mut tribe: Signal<Tribe>) -> Element {
                         .and_then(RelationStrength::from_repr);
                     if let Some(((person1, person2), strength)) = person1.zip(person2).zip(strength)
                     {
-                        tribe.write().add_relation(person1, person2, strength);
+                        history.edit_tribe(|tribe| tribe.add_relation(person1, person2, strength));
                     }
                 },
 
@@ -487,6 +1046,524 @@ fn RelationInput(mut tribe: Signal<Tribe>) -> Element {
                     "Add"
                 }
             }
+
+            div { class: "divider", "OR" }
+
+            RelationPaste { tribe, history }
+        }
+    }
+}
+
+/// Add many relations at once from pasted lines, previewed before adding them.
+#[component]
+fn RelationPaste(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    let mut text = use_signal(String::new);
+    let lines = use_memo(move || import::parse_relations(&text.read()));
+    let n_valid = use_memo(move || lines.read().iter().filter(|l| l.row.is_ok()).count());
+
+    rsx! {
+        fieldset { class: "fieldset",
+            textarea {
+                class: "textarea focus:outline-none w-full font-mono",
+                rows: 4,
+                placeholder: "Harry Potter, Loves, Ginny Weasley",
+                value: text,
+                oninput: move |event| text.set(event.value()),
+            }
+            p { class: "label",
+                Icon { class: "size-[1em]", icon: icons::LdInfo }
+                "One relation per line, separated by commas, semicolons or tabs"
+            }
+        }
+        if !lines.read().is_empty() {
+            table { class: "table table-xs",
+                tbody {
+                    for line in lines.read().iter() {
+                        match &line.row {
+                            Ok(row) => rsx! {
+                                tr {
+                                    td { "{line.number}" }
+                                    td {
+                                        "{row.person1}"
+                                        if !tribe.read().contains_person(&row.person1) {
+                                            span { class: "badge badge-accent badge-xs ml-1", "new" }
+                                        }
+                                    }
+                                    td { "{row.strength}" }
+                                    td {
+                                        "{row.person2}"
+                                        if !tribe.read().contains_person(&row.person2) {
+                                            span { class: "badge badge-accent badge-xs ml-1", "new" }
+                                        }
+                                    }
+                                }
+                            },
+                            Err(error) => rsx! {
+                                tr { class: "bg-error/10 text-error",
+                                    td { "{line.number}" }
+                                    td { colspan: 3,
+                                        span { class: "font-mono", "{line.text}" }
+                                        br {}
+                                        "{error}"
+                                    }
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+        button {
+            class: "btn btn-primary ml-auto block",
+            disabled: n_valid() == 0,
+            onclick: move |_| {
+                history.edit_tribe(|tribe| {
+                    import::add_relations(tribe, lines.read().iter());
+                });
+                text.set(String::new());
+            },
+            "Add {n_valid} relations"
+        }
+    }
+}
+
+#[component]
+fn GroupList(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    const GROUP_PERSON_DATALIST_ID: &str = "group_person_datalist";
+
+    rsx! {
+        datalist { id: GROUP_PERSON_DATALIST_ID,
+            for p in tribe.read().persons() {
+                option { value: "{p}" }
+            }
+        }
+        table { class: "table",
+            thead {
+                th { "Name" }
+                th { "Members" }
+                th { "Together" }
+                th { class: "w-4" }
+            }
+            tbody {
+                for (name , group) in tribe.read().groups() {
+                    tr {
+                        td {
+                            div { "{name}" }
+                            if let Some(relation) = group.relation {
+                                div { class: "text-xs opacity-60", "{relation} each other" }
+                            }
+                        }
+                        td {
+                            div { class: "flex flex-wrap gap-1",
+                                for person in group.members() {
+                                    div {
+                                        key: "{person}",
+                                        class: "badge badge-soft badge-accent overflow-hidden pr-0",
+                                        span { "{person}" }
+                                        button {
+                                            class: "btn-ghost cursor-pointer hover:bg-accent-content h-full pl-1 pr-2",
+                                            onclick: {
+                                                let name = name.to_owned();
+                                                let person = person.to_owned();
+                                                move |_| {
+                                                    history.edit_tribe(|tribe| tribe.remove_from_group(&name, &person));
+                                                }
+                                            },
+                                            Icon {
+                                                class: "size-[1em]",
+                                                icon: icons::LdX,
+                                            }
+                                        }
+                                    }
+                                }
+                                input {
+                                    r#type: "text",
+                                    list: GROUP_PERSON_DATALIST_ID,
+                                    class: "input input-xs focus:outline-none w-24",
+                                    placeholder: "Add...",
+                                    onchange: {
+                                        let name = name.to_owned();
+                                        move |event: Event<FormData>| {
+                                            let person = event.value().trim().to_owned();
+                                            if !person.is_empty() {
+                                                history.edit_tribe(|tribe| tribe.add_to_group(name.as_str(), person));
+                                            }
+                                        }
+                                    },
+                                }
+                            }
+                        }
+                        td { "{group.cohesion}" }
+                        td {
+                            SectionTrashButton {
+                                onclick: {
+                                    let name = name.to_owned();
+                                    move |_| {
+                                        history.edit_tribe(|tribe| tribe.remove_group(&name));
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn GroupInput(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    const GROUP_NAME_ID: &str = "group_name";
+    const GROUP_RELATION_ID: &str = "group_relation";
+    const GROUP_COHESION_ID: &str = "group_cohesion";
+
+    rsx! {
+        SectionAdd { title: "Add a group of persons",
+            form {
+                class: "mx-auto space-y-2",
+                onsubmit: move |event| {
+                    let mut data = event.data.values();
+                    let name = data
+                        .remove(GROUP_NAME_ID)
+                        .map(|val| val.as_value())
+                        .filter(|val| !val.trim().is_empty());
+                    let relation = data
+                        .remove(GROUP_RELATION_ID)
+                        .map(|val| val.as_value())
+                        .and_then(|val| val.parse::<usize>().ok())
+                        .and_then(RelationStrength::from_repr);
+                    let cohesion = data
+                        .remove(GROUP_COHESION_ID)
+                        .map(|val| val.as_value())
+                        .and_then(|val| val.parse::<usize>().ok())
+                        .and_then(GroupCohesion::from_repr)
+                        .unwrap_or_default();
+                    if let Some(name) = name {
+                        history.edit_tribe(|tribe| tribe.add_group(name, relation, cohesion));
+                    }
+                },
+                label { r#for: GROUP_NAME_ID, class: "floating-label",
+                    input {
+                        id: GROUP_NAME_ID,
+                        name: GROUP_NAME_ID,
+                        r#type: "text",
+                        minlength: 1,
+                        class: "input focus:outline-none w-full",
+                        placeholder: "Group name",
+                    }
+                    span { "Group name" }
+                }
+                label { r#for: GROUP_RELATION_ID, class: "floating-label",
+                    select {
+                        id: GROUP_RELATION_ID,
+                        name: GROUP_RELATION_ID,
+                        class: "select focus:outline-none w-full",
+                        option { value: "", "No relation" }
+                        for strength in RelationStrength::iter() {
+                            option { value: strength as usize, "{strength} each other" }
+                        }
+                    }
+                    span { "Relation between members" }
+                }
+                label { r#for: GROUP_COHESION_ID, class: "floating-label",
+                    select {
+                        id: GROUP_COHESION_ID,
+                        name: GROUP_COHESION_ID,
+                        class: "select focus:outline-none w-full",
+                        for cohesion in GroupCohesion::iter() {
+                            option { value: cohesion as usize, "{cohesion}" }
+                        }
+                    }
+                    span { "Seat members together" }
+                }
+                button {
+                    class: "btn btn-primary ml-auto block w-32",
+                    r#type: "submit",
+                    "Add"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AttributeList(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        table { class: "table",
+            thead {
+                th { "Person" }
+                th { "Attributes" }
+            }
+            tbody {
+                for person in tribe.read().persons() {
+                    if tribe.read().person_attributes(person).next().is_some() {
+                        tr {
+                            td { "{person}" }
+                            td {
+                                div { class: "flex flex-wrap gap-1",
+                                    for attribute in tribe.read().person_attributes(person) {
+                                        div {
+                                            key: "{attribute}",
+                                            class: "badge badge-soft badge-secondary overflow-hidden pr-0",
+                                            span { "{attribute}" }
+                                            button {
+                                                class: "btn-ghost cursor-pointer hover:bg-secondary-content h-full pl-1 pr-2",
+                                                onclick: {
+                                                    let person = person.to_owned();
+                                                    let attribute = attribute.to_owned();
+                                                    move |_| {
+                                                        history.edit_tribe(|tribe| tribe.remove_attribute(&person, &attribute));
+                                                    }
+                                                },
+                                                Icon {
+                                                    class: "size-[1em]",
+                                                    icon: icons::LdX,
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AttributeInput(tribe: Signal<Tribe>, history: crate::ProblemHistory) -> Element {
+    const ATTRIBUTE_PERSON_ID: &str = "attribute_person";
+    const ATTRIBUTE_NAME_ID: &str = "attribute_name";
+    const ATTRIBUTE_VALUE_ID: &str = "attribute_value";
+    const ATTRIBUTE_PERSON_DATALIST_ID: &str = "attribute_person_datalist";
+    const ATTRIBUTE_NAME_DATALIST_ID: &str = "attribute_name_datalist";
+    const ATTRIBUTE_VALUE_DATALIST_ID: &str = "attribute_value_datalist";
+
+    rsx! {
+        SectionAdd { title: "Add an attribute to a person",
+            form {
+                class: "mx-auto space-y-2",
+                onsubmit: move |event| {
+                    let mut data = event.data.values();
+                    let mut take = |id: &str| {
+                        data.remove(id)
+                            .map(|val| val.as_value().trim().to_owned())
+                            .filter(|val| !val.is_empty())
+                    };
+                    let person = take(ATTRIBUTE_PERSON_ID);
+                    let name = take(ATTRIBUTE_NAME_ID);
+                    let value = take(ATTRIBUTE_VALUE_ID);
+                    if let Some(((person, name), value)) = person.zip(name).zip(value) {
+                        history.edit_tribe(|tribe| tribe.add_attribute(person, Attribute::new(name, value)));
+                    }
+                },
+                datalist { id: ATTRIBUTE_PERSON_DATALIST_ID,
+                    for p in tribe.read().persons() {
+                        option { value: "{p}" }
+                    }
+                }
+                datalist { id: ATTRIBUTE_NAME_DATALIST_ID,
+                    for name in tribe.read().attribute_names() {
+                        option { value: "{name}" }
+                    }
+                }
+                datalist { id: ATTRIBUTE_VALUE_DATALIST_ID,
+                    for value in tribe.read().attributes().map(|(_, a)| &a.value).collect::<BTreeSet<_>>() {
+                        option { value: "{value}" }
+                    }
+                }
+                label { r#for: ATTRIBUTE_PERSON_ID, class: "floating-label",
+                    input {
+                        id: ATTRIBUTE_PERSON_ID,
+                        name: ATTRIBUTE_PERSON_ID,
+                        r#type: "text",
+                        list: ATTRIBUTE_PERSON_DATALIST_ID,
+                        minlength: 1,
+                        class: "input focus:outline-none w-full",
+                        placeholder: "Person name",
+                    }
+                    span { "Person name" }
+                }
+                label { r#for: ATTRIBUTE_NAME_ID, class: "floating-label",
+                    input {
+                        id: ATTRIBUTE_NAME_ID,
+                        name: ATTRIBUTE_NAME_ID,
+                        r#type: "text",
+                        list: ATTRIBUTE_NAME_DATALIST_ID,
+                        minlength: 1,
+                        class: "input focus:outline-none w-full",
+                        placeholder: "Attribute (e.g. Language)",
+                    }
+                    span { "Attribute" }
+                }
+                label { r#for: ATTRIBUTE_VALUE_ID, class: "floating-label",
+                    input {
+                        id: ATTRIBUTE_VALUE_ID,
+                        name: ATTRIBUTE_VALUE_ID,
+                        r#type: "text",
+                        list: ATTRIBUTE_VALUE_DATALIST_ID,
+                        minlength: 1,
+                        class: "input focus:outline-none w-full",
+                        placeholder: "Value (e.g. French)",
+                    }
+                    span { "Value" }
+                }
+                button {
+                    class: "btn btn-primary ml-auto block w-32",
+                    r#type: "submit",
+                    "Add"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn GoalList(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        table { class: "table",
+            thead {
+                th { "Every table has" }
+                th { class: "w-4" }
+            }
+            tbody {
+                for (i , goal) in settings.read().attribute_goals.iter().enumerate() {
+                    tr {
+                        td { "{goal}" }
+                        td {
+                            SectionTrashButton {
+                                onclick: move |_| {
+                                    history.edit_settings(|settings| {
+                                        settings.attribute_goals.remove(i);
+                                    });
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn GoalInput(
+    tribe: Signal<Tribe>,
+    settings: Signal<SolverSettings>,
+    history: crate::ProblemHistory,
+) -> Element {
+    const GOAL_KIND_ID: &str = "goal_kind";
+    const GOAL_NAME_ID: &str = "goal_name";
+    const GOAL_VALUE_ID: &str = "goal_value";
+    const GOAL_COUNT_ID: &str = "goal_count";
+    const GOAL_NAME_DATALIST_ID: &str = "goal_name_datalist";
+    const GOAL_VALUE_DATALIST_ID: &str = "goal_value_datalist";
+    const KIND_AT_LEAST: &str = "at_least";
+    const KIND_MIX: &str = "mix";
+
+    let mut kind = use_signal(|| KIND_AT_LEAST.to_owned());
+    let mut name = use_signal(String::new);
+
+    rsx! {
+        SectionAdd { title: "Add a goal for every table",
+            form {
+                class: "mx-auto space-y-2",
+                onsubmit: move |event| {
+                    let mut data = event.data.values();
+                    let value = data
+                        .remove(GOAL_VALUE_ID)
+                        .map(|val| val.as_value().trim().to_owned())
+                        .filter(|val| !val.is_empty());
+                    let count = data
+                        .remove(GOAL_COUNT_ID)
+                        .map(|val| val.as_value())
+                        .and_then(|val| val.parse::<u32>().ok())
+                        .unwrap_or(1);
+                    let name = name.read().trim().to_owned();
+                    let goal = match kind.read().as_str() {
+                        _ if name.is_empty() => None,
+                        KIND_MIX => Some(AttributeGoal::Mix { name }),
+                        _ => {
+                            value
+                                .map(|value| AttributeGoal::AtLeast {
+                                    attribute: Attribute::new(name, value),
+                                    count,
+                                })
+                        }
+                    };
+                    if let Some(goal) = goal {
+                        history.edit_settings(|settings| {
+                            settings.attribute_goals.push(goal);
+                        });
+                    }
+                },
+                datalist { id: GOAL_NAME_DATALIST_ID,
+                    for name in tribe.read().attribute_names() {
+                        option { value: "{name}" }
+                    }
+                }
+                datalist { id: GOAL_VALUE_DATALIST_ID,
+                    for value in tribe.read().attribute_values(&name.read()) {
+                        option { value: "{value}" }
+                    }
+                }
+                label { r#for: GOAL_KIND_ID, class: "floating-label",
+                    select {
+                        id: GOAL_KIND_ID,
+                        class: "select focus:outline-none w-full",
+                        onchange: move |event| kind.set(event.value()),
+                        option { value: KIND_AT_LEAST, "At least some persons with an attribute" }
+                        option { value: KIND_MIX, "All the values of an attribute" }
+                    }
+                    span { "Goal" }
+                }
+                label { r#for: GOAL_NAME_ID, class: "floating-label",
+                    input {
+                        id: GOAL_NAME_ID,
+                        r#type: "text",
+                        list: GOAL_NAME_DATALIST_ID,
+                        minlength: 1,
+                        class: "input focus:outline-none w-full",
+                        placeholder: "Attribute (e.g. Language)",
+                        oninput: move |event| name.set(event.value()),
+                    }
+                    span { "Attribute" }
+                }
+                if *kind.read() == KIND_AT_LEAST {
+                    label { r#for: GOAL_VALUE_ID, class: "floating-label",
+                        input {
+                            id: GOAL_VALUE_ID,
+                            name: GOAL_VALUE_ID,
+                            r#type: "text",
+                            list: GOAL_VALUE_DATALIST_ID,
+                            minlength: 1,
+                            class: "input focus:outline-none w-full",
+                            placeholder: "Value (e.g. French)",
+                        }
+                        span { "Value" }
+                    }
+                    label { r#for: GOAL_COUNT_ID, class: "floating-label",
+                        input {
+                            id: GOAL_COUNT_ID,
+                            name: GOAL_COUNT_ID,
+                            r#type: "number",
+                            min: 1,
+                            step: 1,
+                            value: 1,
+                            class: "input focus:outline-none w-full",
+                            placeholder: "Number of persons",
+                        }
+                        span { "Number of persons" }
+                    }
+                }
+                button {
+                    class: "btn btn-primary ml-auto block w-32",
+                    r#type: "submit",
+                    "Add"
+                }
+            }
         }
     }
 }
@@ -1,9 +1,15 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
 use dioxus::prelude::*;
 use dioxus_free_icons::{Icon, icons::ld_icons as icons};
+use futures_util::StreamExt;
 
 use crate::SolutionState;
 use crate::app::ui::{AssignedSchema, CardSimple, UnassignedSchema};
-use crate::logic::model::Assignment;
+use crate::logic::model::{self, PersonName, TableName};
+use crate::logic::monitor::{CancelToken, Monitor, Progress};
+use crate::logic::solver::{self, Objective, SolverSettings};
 
 #[component]
 pub fn Page() -> Element {
@@ -14,7 +20,10 @@ pub fn Page() -> Element {
         Schema { pb: pb.clone(), solution: solution.clone() }
         div { class: "p-2 pb-8 lg:px-8",
             ControlBar { class: "py-4", pb: pb.clone(), solution: solution.clone() }
-            AssignmentSection { solution }
+            if *solution.state.read() != SolutionState::Missing {
+                ScoreReport { class: "pb-4", pb: pb.clone(), solution: solution.clone() }
+            }
+            AssignmentSection { pb, solution }
         }
     }
 }
@@ -27,8 +36,310 @@ fn ControlBar(
 ) -> Element {
     rsx! {
         div { class: format!("flex justify-between items-center {}", class),
-            SolveText { state: solution.state }
-            SolveButton { pb, solution }
+            SolveText {
+                state: solution.state,
+                progress: solution.progress,
+                cancel: solution.cancel,
+                objective: pb.settings.read().objective,
+            }
+            div { class: "flex gap-2 items-center",
+                RoundsInput { settings: pb.settings, history: pb.history }
+                SearchOptions { settings: pb.settings, history: pb.history }
+                ObjectiveSelect { settings: pb.settings, history: pb.history }
+                if solution.cancel.read().is_some() {
+                    StopButton { cancel: solution.cancel }
+                } else if *solution.state.read() == SolutionState::Outdated {
+                    ResolveButton { pb: pb.clone(), solution: solution.clone() }
+                }
+                SolveButton { pb, solution }
+            }
+        }
+    }
+}
+
+#[component]
+fn RoundsInput(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        label { class: "input w-32",
+            span { class: "label", "Rounds" }
+            input {
+                r#type: "number",
+                min: 1,
+                step: 1,
+                value: settings.read().rounds,
+                onchange: move |event| {
+                    if let Ok(rounds) = event.value().parse::<u32>() {
+                        history.edit_settings(|settings| settings.rounds = rounds.max(1));
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Settings of how long and how widely to search for better assignments.
+#[component]
+fn SearchOptions(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        div { class: "dropdown dropdown-end",
+            div {
+                tabindex: 0,
+                role: "button",
+                class: "btn aspect-square p-0",
+                title: "Search options",
+                Icon { icon: icons::LdSlidersHorizontal }
+            }
+            div {
+                tabindex: 0,
+                class: "dropdown-content bg-base-100 rounded-box z-10 p-2 shadow-sm flex flex-col gap-2",
+                StartsInput { settings, history }
+                AlternativesInput { settings, history }
+                TimeBudgetInput { settings, history }
+                SeedInput { settings, history }
+            }
+        }
+    }
+}
+
+#[component]
+fn StartsInput(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        label { class: "input w-44",
+            span { class: "label", "Starts" }
+            input {
+                r#type: "number",
+                min: 1,
+                step: 1,
+                value: settings.read().starts,
+                onchange: move |event| {
+                    if let Ok(starts) = event.value().parse::<u32>() {
+                        history.edit_settings(|settings| settings.starts = starts.max(1));
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn AlternativesInput(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        label { class: "input w-44",
+            span { class: "label", "Alternatives" }
+            input {
+                r#type: "number",
+                min: 1,
+                step: 1,
+                value: settings.read().alternatives,
+                onchange: move |event| {
+                    if let Ok(alternatives) = event.value().parse::<u32>() {
+                        history.edit_settings(|settings| {
+                            settings.alternatives = alternatives.max(1);
+                        });
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn TimeBudgetInput(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    let seconds = settings
+        .read()
+        .time_budget
+        .map_or(String::new(), |budget| budget.as_secs_f32().to_string());
+
+    rsx! {
+        label { class: "input w-44",
+            span { class: "label", "Search for" }
+            input {
+                r#type: "number",
+                min: 0,
+                step: 1,
+                placeholder: "-",
+                value: seconds,
+                onchange: move |event| {
+                    let value = event.value();
+                    let time_budget = match value.parse::<f32>() {
+                        // Empty or zero to stop at the first local optimum
+                        _ if value.is_empty() => Some(None),
+                        Ok(0.0) => Some(None),
+                        // Ignore what is not a duration, such as negative or infinite ones
+                        Ok(seconds) => Duration::try_from_secs_f32(seconds).ok().map(Some),
+                        Err(_) => None,
+                    };
+                    if let Some(time_budget) = time_budget {
+                        history.edit_settings(|settings| settings.time_budget = time_budget);
+                    }
+                },
+            }
+            span { class: "label", "s" }
+        }
+    }
+}
+
+#[component]
+fn SeedInput(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    rsx! {
+        label { class: "input w-44",
+            span { class: "label", "Seed" }
+            input {
+                r#type: "number",
+                min: 0,
+                step: 1,
+                value: settings.read().seed,
+                onchange: move |event| {
+                    if let Ok(seed) = event.value().parse::<u64>() {
+                        history.edit_settings(|settings| settings.seed = seed);
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn ObjectiveSelect(settings: Signal<SolverSettings>, history: crate::ProblemHistory) -> Element {
+    const BLEND_WEIGHT: f32 = 1.0;
+
+    let current = settings.read().objective;
+    // Keep the weight chosen before when selecting the blend again
+    let blend_weight = match current {
+        Objective::Blend(weight) => weight,
+        _ => BLEND_WEIGHT,
+    };
+    let objectives = [
+        Objective::Total,
+        Objective::Fairness,
+        Objective::Blend(blend_weight),
+    ];
+
+    rsx! {
+        label { class: "select w-56",
+            span { class: "label", "Minimize" }
+            select {
+                onchange: move |event| {
+                    let objective = event
+                        .value()
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| objectives.get(i).copied());
+                    if let Some(objective) = objective {
+                        history.edit_settings(|settings| settings.objective = objective);
+                    }
+                },
+                for (i , objective) in objectives.iter().enumerate() {
+                    option {
+                        value: i,
+                        selected: std::mem::discriminant(objective) == std::mem::discriminant(&current),
+                        {objective_text(objective)}
+                    }
+                }
+            }
+        }
+        if let Objective::Blend(weight) = current {
+            label {
+                class: "input w-44",
+                title: "How much the worst-off guest counts compared to the total cost",
+                span { class: "label", "Worst-off weight" }
+                input {
+                    r#type: "number",
+                    min: 0,
+                    step: 0.5,
+                    value: weight,
+                    onchange: move |event| {
+                        let weight = event.value().parse::<f32>().ok().filter(|w| w.is_finite());
+                        if let Some(weight) = weight {
+                            history.edit_settings(|settings| {
+                                settings.objective = Objective::Blend(weight.max(0.0));
+                            });
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn objective_text(objective: &Objective) -> &'static str {
+    match objective {
+        Objective::Total => "Total cost",
+        Objective::Fairness => "Worst-off guest",
+        Objective::Blend(_) => "Both",
+    }
+}
+
+/// What the value of an objective measures.
+fn objective_value_text(objective: &Objective) -> &'static str {
+    match objective {
+        Objective::Total => "total cost",
+        Objective::Fairness => "worst-off cost",
+        Objective::Blend(_) => "blended cost",
+    }
+}
+
+#[component]
+fn ScoreReport(
+    pb: crate::ProblemSignal,
+    solution: crate::SolutionSignal,
+    #[props(default)] class: &'static str,
+) -> Element {
+    // Scored on the interface thread, which is quick next to solving and follows every move
+    let score = use_memo(move || {
+        crate::logic::solver::score(
+            &pb.tables.read(),
+            &pb.tribe.read(),
+            pb.settings.read().clone(),
+            &solution.assignment.read(),
+        )
+        .ok()
+    });
+
+    let closed_tables = use_memo(move || {
+        solver::closed_tables(&pb.tables.read(), &pb.tribe.read(), &pb.settings.read())
+            .into_iter()
+            .collect::<Vec<_>>()
+    });
+
+    rsx! {
+        if let Some(score) = &*score.read() {
+            div { class: format!("stats shadow bg-base-100 {}", class),
+                div { class: "stat",
+                    div { class: "stat-title", "Total cost" }
+                    div { class: "stat-value", "{score.total_cost:.1}" }
+                    div {
+                        class: "stat-desc",
+                        title: "No assignment can cost less than {score.lower_bound:.1}",
+                        "Lower is better, at most {score.gap():.1} above the best possible"
+                    }
+                }
+                div { class: "stat",
+                    div { class: "stat-title", "Worst-off guest" }
+                    if let Some((person, cost)) = &score.worst_person {
+                        div { class: "stat-value", "{person}" }
+                        div { class: "stat-desc", "Cost of {cost:.1}" }
+                    } else {
+                        div { class: "stat-value", "-" }
+                    }
+                }
+                if let Some(stats) = &*solution.stats.read() {
+                    div { class: "stat",
+                        div { class: "stat-title", "Assignments tried" }
+                        div { class: "stat-value", "{stats.iterations}" }
+                        div { class: "stat-desc",
+                            "In {stats.elapsed.as_secs_f32():.1}s with seed {stats.seed}"
+                        }
+                    }
+                }
+                if !closed_tables.read().is_empty() {
+                    div { class: "stat",
+                        div { class: "stat-title", "Closed tables" }
+                        div { class: "stat-value", "{closed_tables.read().len()}" }
+                        div { class: "stat-desc", {closed_tables.read().join(", ")} }
+                    }
+                }
+            }
         }
     }
 }
@@ -52,32 +363,154 @@ fn Schema(pb: crate::ProblemSignal, solution: crate::SolutionSignal) -> Element
     }
 }
 
+/// Solve in the background, from scratch or moving as few persons as possible from the
+/// current rounds.
+///
+/// Does nothing while another solve is running, which the cancellation token of the solution
+/// tells.
+fn start_solving(pb: &crate::ProblemSignal, solution: &crate::SolutionSignal, minimal: bool) {
+    const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+    if solution.cancel.peek().is_some() {
+        return;
+    }
+
+    let tables = pb.tables.peek().clone();
+    let tribe = pb.tribe.peek().clone();
+    let settings = pb.settings.peek().clone();
+    let previous_rounds = match minimal {
+        true => solution.current_rounds(),
+        false => Vec::new(),
+    };
+    let cancel = CancelToken::new();
+    let (progress_sender, mut progress_receiver) = futures_channel::mpsc::unbounded();
+
+    let mut solution = solution.clone();
+    solution.state.set(SolutionState::Solving);
+    solution.progress.set(None);
+    solution.cancel.set(Some(cancel.clone()));
+
+    let mut progress = solution.progress;
+    spawn(async move {
+        while let Some(update) = progress_receiver.next().await {
+            progress.set(Some(update));
+        }
+    });
+    spawn(async move {
+        let result = crate::background::run(move || async move {
+            let mut monitor = Monitor::new().with_cancel(cancel).with_progress(
+                PROGRESS_INTERVAL,
+                move |update| {
+                    // The receiver is only gone if the interface was closed
+                    let _ = progress_sender.unbounded_send(update.clone());
+                },
+            );
+            solver::resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings,
+                &previous_rounds,
+                &mut monitor,
+            )
+            .await
+        })
+        .await;
+        solution.progress.set(None);
+        solution.cancel.set(None);
+        // The problem may have changed while solving
+        let outdated = *solution.state.peek() == SolutionState::Outdated;
+        match result {
+            Ok(output) => {
+                if minimal {
+                    solution.update_rounds(output.rounds);
+                } else {
+                    solution.set_rounds(output.rounds);
+                }
+                solution.alternatives.set(output.alternatives);
+                solution.stats.set(Some(output.stats));
+                solution.state.set(match outdated {
+                    true => SolutionState::Outdated,
+                    false => SolutionState::Valid,
+                });
+            }
+            Err(err) => {
+                solution.state.set(SolutionState::Error(err));
+            }
+        }
+    });
+}
+
 #[component]
-fn SolveButton(pb: crate::ProblemSignal, solution: crate::SolutionSignal) -> Element {
+fn StopButton(cancel: Signal<Option<CancelToken>>) -> Element {
     rsx! {
         button {
-            class: "btn btn-primary",
+            class: "btn btn-error",
+            disabled: cancel.read().is_none(),
             onclick: move |_| {
-                match crate::logic::solver::solve(&pb.tables.read(), &pb.tribe.read()) {
-                    Ok(assignment) => {
-                        solution.state.set(SolutionState::Valid);
-                        solution.assignment.set(assignment);
-                    }
-                    Err(err) => {
-                        solution.state.set(SolutionState::Error(err));
-                    }
+                if let Some(cancel) = &*cancel.read() {
+                    cancel.cancel();
                 }
             },
-            disabled: solve_disabled(solution.state),
-            "{solve_text(solution.state)}"
+            Icon { icon: icons::LdCircleStop }
+            "Stop"
+        }
+    }
+}
+
+#[component]
+fn SolveButton(pb: crate::ProblemSignal, solution: crate::SolutionSignal) -> Element {
+    rsx! {
+        button {
+            class: "btn btn-primary",
+            onclick: {
+                let solution = solution.clone();
+                move |_| start_solving(&pb, &solution, false)
+            },
+            disabled: solve_disabled(solution.state, solution.cancel),
+            if solution.cancel.read().is_some() {
+                span { class: "loading loading-spinner" }
+            }
+            "{solve_text(solution.state, solution.cancel)}"
+        }
+    }
+}
+
+#[component]
+fn ResolveButton(pb: crate::ProblemSignal, solution: crate::SolutionSignal) -> Element {
+    rsx! {
+        button {
+            class: "btn btn-secondary",
+            onclick: {
+                let solution = solution.clone();
+                move |_| start_solving(&pb, &solution, true)
+            },
+            "Solve again (keep changes minimal)"
         }
     }
 }
 
 #[component]
-fn SolveText(state: Signal<SolutionState>) -> Element {
+fn SolveText(
+    state: Signal<SolutionState>,
+    progress: Signal<Option<Progress>>,
+    cancel: Signal<Option<CancelToken>>,
+    objective: Objective,
+) -> Element {
     rsx! {
-        if *state.read() == SolutionState::Missing {
+        if cancel.read().is_some() {
+            div { role: "alert", class: "alert",
+                span { class: "loading loading-spinner loading-sm" }
+                if let Some(progress) = &*progress.read() {
+                    span {
+                        "Solving round {progress.round + 1}, "
+                        "{progress.iteration} assignments tried in {progress.elapsed.as_secs_f32():.1}s, "
+                        "best {objective_value_text(&objective)} {progress.objective:.1}"
+                    }
+                } else {
+                    span { "Solving..." }
+                }
+            }
+        } else if *state.read() == SolutionState::Missing {
             div { role: "alert", class: "alert alert-info",
                 Icon { icon: icons::LdInfo }
                 span { "There is no ongoing solution" }
@@ -85,7 +518,27 @@ fn SolveText(state: Signal<SolutionState>) -> Element {
         } else if *state.read() == SolutionState::Outdated {
             div { role: "alert", class: "alert alert-warning",
                 Icon { icon: icons::LdTriangleAlert }
-                span { "The problem has changed since the last solution" }
+                span { "The problem or a round has changed since the last solution" }
+            }
+        } else if let SolutionState::Error(solver::SolverError::NoSolution(reasons)) = &(*state.read()) {
+            div { role: "alert", class: "alert alert-error",
+                Icon { icon: icons::LdCircleX }
+                div {
+                    span { "No solution could be found:" }
+                    ul { class: "list-disc list-inside",
+                        for reason in reasons.iter() {
+                            li { "{reason}" }
+                        }
+                    }
+                }
+            }
+        } else if let SolutionState::Error(solver::SolverError::NotSeated(persons)) = &(*state.read()) {
+            div { role: "alert", class: "alert alert-warning",
+                Icon { icon: icons::LdTriangleAlert }
+                span {
+                    "No seats were found for {persons.join(\", \")}, although there may be some. "
+                    "More starts in the search options may find them."
+                }
             }
         } else if let SolutionState::Error(error) = &(*state.read()) {
             div { role: "alert", class: "alert alert-error",
@@ -98,39 +551,217 @@ fn SolveText(state: Signal<SolutionState>) -> Element {
     }
 }
 
-fn solve_text(state: Signal<SolutionState>) -> &'static str {
+fn solve_text(state: Signal<SolutionState>, cancel: Signal<Option<CancelToken>>) -> &'static str {
+    if cancel.read().is_some() {
+        return "Solving";
+    }
     match *state.read() {
         SolutionState::Missing => "Solve",
+        SolutionState::Solving => "Solving",
         SolutionState::Outdated => "Solve again",
         SolutionState::Error(_) => "Solve again",
         SolutionState::Valid => "Up to date",
     }
 }
 
-fn solve_disabled(state: Signal<SolutionState>) -> bool {
-    *state.read() == SolutionState::Valid
+fn solve_disabled(state: Signal<SolutionState>, cancel: Signal<Option<CancelToken>>) -> bool {
+    cancel.read().is_some() || *state.read() == SolutionState::Valid
 }
 
 #[component]
-fn AssignmentSection(solution: crate::SolutionSignal) -> Element {
+fn AssignmentSection(pb: crate::ProblemSignal, solution: crate::SolutionSignal) -> Element {
     rsx! {
-        if *solution.state.read() == SolutionState::Missing {
+        if solution.rounds.read().is_empty() {
             AssignmentSkeleton {}
         } else {
-            AssignmentList { assignment: solution.assignment }
+            RoundTabs { solution: solution.clone() }
+            AlternativeTabs { solution: solution.clone() }
+            AssignmentList { pb, solution }
+        }
+    }
+}
+
+#[component]
+fn RoundTabs(solution: crate::SolutionSignal) -> Element {
+    let n_rounds = solution.rounds.read().len();
+
+    rsx! {
+        if n_rounds > 1 {
+            div { role: "tablist", class: "tabs tabs-box mb-4",
+                for i in 0..n_rounds {
+                    a {
+                        role: "tab",
+                        class: if i == *solution.round.read() { "tab tab-active" } else { "tab" },
+                        onclick: {
+                            let mut solution = solution.clone();
+                            move |_| solution.select_round(i)
+                        },
+                        "Round {i + 1}"
+                    }
+                }
+            }
         }
     }
 }
 
+/// Choose which of the best assignments found for the round is displayed.
+///
+/// Later rounds were solved after the first option, so choosing another one makes them
+/// outdated.
 #[component]
-fn AssignmentList(assignment: Signal<Assignment>) -> Element {
+fn AlternativeTabs(solution: crate::SolutionSignal) -> Element {
+    let mut assignment = solution.assignment;
+    let mut state = solution.state;
+    let alternatives = use_memo(move || {
+        solution
+            .alternatives
+            .read()
+            .get(*solution.round.read())
+            .cloned()
+            .unwrap_or_default()
+    });
+    let has_later_rounds = *solution.round.read() + 1 < solution.rounds.read().len();
+    let is_first = alternatives
+        .read()
+        .first()
+        .is_none_or(|first| *assignment.read() == first.assignment);
+
+    rsx! {
+        if alternatives.read().len() > 1 {
+            div { class: "flex gap-2 items-center mb-4",
+                span { class: "text-sm", "Alternatives" }
+                div { role: "tablist", class: "tabs tabs-box tabs-sm",
+                    for (i , alternative) in alternatives.read().iter().enumerate() {
+                        a {
+                            role: "tab",
+                            class: if *assignment.read() == alternative.assignment { "tab tab-active" } else { "tab" },
+                            title: "Cost of {alternative.cost:.1}",
+                            onclick: {
+                                let alternative = alternative.assignment.clone();
+                                move |_| {
+                                    if *assignment.peek() == alternative {
+                                        return;
+                                    }
+                                    assignment.set(alternative.clone());
+                                    if has_later_rounds && *state.peek() == SolutionState::Valid {
+                                        state.set(SolutionState::Outdated);
+                                    }
+                                }
+                            },
+                            "Option {i + 1} ({alternative.cost:.1})"
+                        }
+                    }
+                }
+                if has_later_rounds && !is_first {
+                    span { class: "text-sm text-warning flex items-center gap-1",
+                        Icon { icon: icons::LdTriangleAlert }
+                        "Later rounds were solved after option 1, solve again to take this choice into account"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AssignmentList(pb: crate::ProblemSignal, solution: crate::SolutionSignal) -> Element {
+    let mut assignment = solution.assignment;
+    let settings = pb.settings;
+    let mut history = pb.history;
+    let closed_tables = use_memo(move || {
+        solver::closed_tables(&pb.tables.read(), &pb.tribe.read(), &settings.read())
+    });
+    let moved = use_memo(move || {
+        let previous_rounds = solution.previous_rounds.read();
+        match previous_rounds.get(*solution.round.read()) {
+            Some(previous) => crate::logic::model::moved_persons(previous, &assignment.read()),
+            None => BTreeSet::new(),
+        }
+    });
+    let mut dragged = use_signal(|| None::<PersonName>);
+    let mut pin_moves = use_signal(|| false);
+
+    // Drop the dragged person at a table, swapping with a person if dropped on one
+    let mut drop_at = move |table: TableName, target: Option<PersonName>| {
+        let Some(person) = dragged.take() else {
+            return;
+        };
+        let n_seats = pb.tables.read().get(&table).map_or(0, |t| t.n_seats) as usize;
+        let mut assignment = assignment.write();
+        let Some(from) = assignment
+            .iter()
+            .find(|(_, persons)| persons.contains(&person))
+            .map(|(t, _)| t.clone())
+        else {
+            return;
+        };
+        let mut pins = vec![(person.clone(), table.clone())];
+        let done = match target {
+            Some(target) if target != person => {
+                pins.push((target.clone(), from));
+                model::swap_persons(&mut assignment, &person, &target)
+            }
+            None if from != table && assignment[&table].len() < n_seats => {
+                model::move_person(&mut assignment, &person, &table)
+            }
+            _ => false,
+        };
+        let pin = *pin_moves.peek();
+        let locked = pins
+            .iter()
+            .any(|(p, _)| settings.peek().locks.contains_key(p));
+        // Only write when needed, as changing settings outdates the solution
+        if done && (pin || locked) {
+            history.edit_settings(|settings| {
+                // Locks follow the guests
+                for (person, table) in pins.iter() {
+                    if let Some(locked) = settings.locks.get_mut(person) {
+                        *locked = table.clone();
+                    }
+                }
+                if pin {
+                    settings.pins.extend(pins);
+                }
+            });
+        }
+    };
+
+    let n_locks = settings.read().locks.len();
+
     rsx! {
+        div { class: "flex justify-between items-center mb-4",
+            label { class: "label",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-sm",
+                    checked: pin_moves(),
+                    onchange: move |event| pin_moves.set(event.checked()),
+                }
+                "Pin guests I move so that the next solve keeps them"
+            }
+            button {
+                class: "btn btn-sm",
+                disabled: n_locks == 0,
+                onclick: move |_| history.edit_settings(|settings| settings.locks.clear()),
+                Icon { icon: icons::LdLockOpen }
+                "Clear {n_locks} locks"
+            }
+        }
         div { class: "flex flex-wrap gap-4",
             for table_name in assignment.read().keys().cloned() {
                 div { class: "flex-1",
                     TableCard {
                         name: table_name.clone(),
-                        group: assignment.map(move |a| &a[&table_name]),
+                        closed: closed_tables.read().contains(&table_name),
+                        group: assignment.map({
+                            let table_name = table_name.clone();
+                            move |a| &a[&table_name]
+                        }),
+                        moved,
+                        settings,
+                        history: pb.history,
+                        ondragstart: move |person| dragged.set(Some(person)),
+                        ondrop: move |target| drop_at(table_name.clone(), target),
                     }
                 }
             }
@@ -150,14 +781,71 @@ fn AssignmentSkeleton(#[props(default = 4)] element_count: usize) -> Element {
 }
 
 #[component]
-fn TableCard(name: String, group: MappedSignal<Vec<String>>) -> Element {
+fn TableCard(
+    name: String,
+    /// Whether the solver left the table empty on purpose.
+    closed: bool,
+    group: MappedSignal<Vec<String>>,
+    moved: Memo<BTreeSet<PersonName>>,
+    settings: Signal<SolverSettings>,
+    history: crate::ProblemHistory,
+    ondragstart: EventHandler<PersonName>,
+    /// Called with the person dropped on, if any.
+    ondrop: EventHandler<Option<PersonName>>,
+) -> Element {
     rsx! {
-        CardSimple { title: "Table {name}",
-            table { class: "table",
-                tbody {
-                    for person in group.read().iter() {
-                        tr {
-                            td { key: person, "{person}" }
+        div {
+            ondragover: move |event| event.prevent_default(),
+            ondrop: move |event| {
+                event.prevent_default();
+                ondrop.call(None);
+            },
+            CardSimple { title: "Table {name}",
+                if closed && group.read().is_empty() {
+                    div { class: "badge badge-ghost", "Closed" }
+                }
+                table { class: "table",
+                    tbody {
+                        for person in group.read().iter().cloned() {
+                            tr {
+                                key: "{person}",
+                                class: "cursor-grab",
+                                draggable: true,
+                                ondragstart: {
+                                    let person = person.clone();
+                                    move |_| ondragstart.call(person.clone())
+                                },
+                                ondrop: {
+                                    let person = person.clone();
+                                    move |event: DragEvent| {
+                                        event.prevent_default();
+                                        event.stop_propagation();
+                                        ondrop.call(Some(person.clone()));
+                                    }
+                                },
+                                td {
+                                    "{person}"
+                                    if moved.read().contains(&person) {
+                                        span { class: "badge badge-warning badge-sm ml-2", "Moved" }
+                                    }
+                                    LockButton { person: person.clone(), table: name.clone(), settings, history }
+                                    if settings.read().pins.get(&person) == Some(&name) {
+                                        button {
+                                            class: "btn btn-ghost btn-xs ml-2",
+                                            title: "Unpin",
+                                            onclick: {
+                                                let person = person.clone();
+                                                move |_| {
+                                                    history.edit_settings(|settings| {
+                                                        settings.pins.remove(&person);
+                                                    });
+                                                }
+                                            },
+                                            Icon { icon: icons::LdPin, width: 14, height: 14 }
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
                 }
@@ -165,3 +853,35 @@ fn TableCard(name: String, group: MappedSignal<Vec<String>>) -> Element {
         }
     }
 }
+
+/// Toggle keeping a person at this table when solving again.
+#[component]
+fn LockButton(
+    person: PersonName,
+    table: TableName,
+    settings: Signal<SolverSettings>,
+    history: crate::ProblemHistory,
+) -> Element {
+    let locked = settings.read().locks.get(&person) == Some(&table);
+
+    rsx! {
+        button {
+            class: if locked { "btn btn-ghost btn-xs ml-2" } else { "btn btn-ghost btn-xs ml-2 opacity-30" },
+            title: if locked { "Unlock" } else { "Lock at this table" },
+            onclick: move |_| {
+                history.edit_settings(|settings| {
+                    if locked {
+                        settings.locks.remove(&person);
+                    } else {
+                        settings.locks.insert(person.clone(), table.clone());
+                    }
+                });
+            },
+            if locked {
+                Icon { icon: icons::LdLock, width: 14, height: 14 }
+            } else {
+                Icon { icon: icons::LdLockOpen, width: 14, height: 14 }
+            }
+        }
+    }
+}
@@ -142,7 +142,7 @@ pub fn TableAndChairs(
             // All the chairs
             for ((angle , tx , ty) , empty) in positions.clone().zip(seats_is_empty) {
                 div {
-                    class: concat!("absolute top-1/2 left-1/2 -translate-x-1/2 -translate-y-1/2"),
+                    class: "absolute top-1/2 left-1/2 -translate-x-1/2 -translate-y-1/2",
                     style: "transform: translateX({tx}px) translateY({ty}px);",
                     Rotated { angle,
                         ArmchairWithMaybePerson { empty }
@@ -217,3 +217,21 @@ pub fn AssignedSchema(tables: Signal<Tables>, assignment: Signal<Assignment>) ->
         }
     }
 }
+
+/// Have the browser save some text as a file.
+pub fn download(file_name: &str, mime_type: &str, content: String) {
+    let script = document::eval(
+        r#"
+        const [fileName, mimeType, content] = await dioxus.recv();
+        const url = URL.createObjectURL(new Blob([content], { type: mimeType }));
+        const link = document.createElement("a");
+        link.href = url;
+        link.download = fileName;
+        link.click();
+        URL.revokeObjectURL(url);
+        "#,
+    );
+    if let Err(err) = script.send((file_name, mime_type, content)) {
+        tracing::error!("could not download {file_name}: {err}");
+    }
+}
@@ -0,0 +1,30 @@
+use std::future::Future;
+
+/// Run a computation in a background thread, without freezing the interface.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn run<T, F>(job: impl FnOnce() -> F + Send + 'static) -> T
+where
+    T: Send + 'static,
+    F: Future<Output = T>,
+{
+    let (sender, receiver) = futures_channel::oneshot::channel();
+    std::thread::spawn(move || {
+        // The receiver is only gone if the interface was closed
+        let _ = sender.send(futures_executor::block_on(job()));
+    });
+    receiver.await.expect("the background job panicked")
+}
+
+/// Run a computation on the main thread, which it must share with the interface by pausing
+/// now and then.
+///
+/// Browsers only give other threads to pages through a worker script, which the Dioxus build
+/// does not produce. The page thus stays busy for the work between two pauses, and for what the
+/// interface computes itself, such as the score of the shown assignment.
+#[cfg(target_arch = "wasm32")]
+pub async fn run<T, F>(job: impl FnOnce() -> F) -> T
+where
+    F: Future<Output = T>,
+{
+    job().await
+}
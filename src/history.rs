@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+/// Undo and redo stacks of reversible edits.
+///
+/// An edit is reverted by applying it, which must turn it into the edit that reapplies it, as
+/// done when swapping a state with a previous one.
+#[derive(Clone, Debug)]
+pub struct History<T> {
+    /// Oldest edits first, so that the oldest is forgotten in constant time.
+    undo: VecDeque<T>,
+    redo: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl<T> History<T> {
+    pub const DEFAULT_CAPACITY: usize = 100;
+
+    /// An empty history keeping at most `capacity` edits to undo.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Record an edit that was just made, forgetting the edits that were undone.
+    pub fn record(&mut self, edit: T) {
+        self.redo.clear();
+        self.undo.push_back(edit);
+        if self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Revert the last edit, returning whether there was one.
+    pub fn undo(&mut self, apply: impl FnOnce(&mut T)) -> bool {
+        Self::transfer(&mut self.undo, &mut self.redo, apply)
+    }
+
+    /// Reapply the last reverted edit, returning whether there was one.
+    pub fn redo(&mut self, apply: impl FnOnce(&mut T)) -> bool {
+        Self::transfer(&mut self.redo, &mut self.undo, apply)
+    }
+
+    fn transfer(from: &mut VecDeque<T>, to: &mut VecDeque<T>, apply: impl FnOnce(&mut T)) -> bool {
+        match from.pop_back() {
+            Some(mut edit) => {
+                apply(&mut edit);
+                to.push_back(edit);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let mut state = 0;
+        let mut history = History::new(2);
+        fn swap(state: &mut i32) -> impl FnOnce(&mut i32) + '_ {
+            move |edit| std::mem::swap(state, edit)
+        }
+
+        assert!(!history.can_undo());
+        for value in 1..=3 {
+            history.record(state);
+            state = value;
+        }
+
+        assert!(history.undo(swap(&mut state)));
+        assert_eq!(state, 2);
+        assert!(history.undo(swap(&mut state)));
+        assert_eq!(state, 1);
+        // The oldest edit was forgotten
+        assert!(!history.undo(swap(&mut state)));
+        assert_eq!(state, 1);
+
+        assert!(history.redo(swap(&mut state)));
+        assert_eq!(state, 2);
+
+        history.record(state);
+        state = 4;
+        assert!(!history.can_redo());
+        assert!(history.undo(swap(&mut state)));
+        assert_eq!(state, 2);
+    }
+}
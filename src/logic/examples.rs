@@ -1,4 +1,5 @@
 use super::model::{RelationStrength as S, TableType, Tables, Tribe};
+use super::random::Rng;
 
 pub fn empty() -> (Tribe, Tables) {
     (Default::default(), Default::default())
@@ -59,6 +60,43 @@ pub fn harry_potter() -> (Tribe, Tables) {
     )
 }
 
+/// A tribe with random relations, every person having a few on average, and just enough
+/// tables of the given size to seat everyone.
+pub fn random(n_persons: usize, table_size: u32, seed: u64) -> (Tribe, Tables) {
+    const RELATIONS_PER_PERSON: usize = 3;
+    const STRENGTHS: [S; 4] = [S::Hates, S::Dislikes, S::Likes, S::Loves];
+
+    let mut rng = Rng::new(seed);
+    let names = (0..n_persons)
+        .map(|i| format!("Person {i:04}"))
+        .collect::<Vec<_>>();
+    let mut tribe = Tribe::new();
+    for name in names.iter() {
+        tribe.add_person(name);
+    }
+    for _ in 0..n_persons * RELATIONS_PER_PERSON {
+        let p1 = rng.choose(&names).unwrap();
+        let p2 = rng.choose(&names).unwrap();
+        if p1 != p2 {
+            tribe.add_relation(p1, p2, *rng.choose(&STRENGTHS).unwrap());
+        }
+    }
+
+    let n_tables = n_persons.div_ceil(table_size as usize);
+    let tables = (0..n_tables)
+        .map(|t| {
+            let name = format!("Table {t:03}");
+            (
+                name,
+                TableType {
+                    n_seats: table_size,
+                },
+            )
+        })
+        .collect();
+    (tribe, tables)
+}
+
 type Relation = (&'static str, S, &'static str);
 
 fn make_tribe(relations: &[Relation]) -> Tribe {
@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::logic::model::{Assignment, PersonNameRef, RelationStrength, TableNameRef, Tribe};
+
+fn strength_color(strength: RelationStrength) -> &'static str {
+    match strength {
+        RelationStrength::Hates => "red",
+        RelationStrength::Dislikes => "orange",
+        RelationStrength::Likes => "blue",
+        RelationStrength::Loves => "green",
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The table of every seated person.
+fn person_tables(assignment: &Assignment) -> BTreeMap<&PersonNameRef, &TableNameRef> {
+    assignment
+        .iter()
+        .flat_map(|(table, persons)| persons.iter().map(move |p| (p.as_str(), table.as_str())))
+        .collect()
+}
+
+/// The tribe in the Graphviz DOT format, with tables of the assignment as clusters.
+///
+/// The seed the assignment was solved with is kept as a graph attribute.
+pub fn to_dot(tribe: &Tribe, assignment: Option<&Assignment>, seed: Option<u64>) -> String {
+    let tables = assignment.map(person_tables).unwrap_or_default();
+
+    // Writing to a String cannot fail
+    let mut out = String::new();
+    writeln!(out, "digraph tribe {{").unwrap();
+    if let Some(seed) = seed {
+        writeln!(out, "  seed={seed};").unwrap();
+    }
+    for (i, (table, persons)) in assignment.into_iter().flatten().enumerate() {
+        let persons = persons.iter().filter(|p| tribe.contains_person(p));
+        writeln!(out, "  subgraph cluster_{i} {{").unwrap();
+        writeln!(out, "    label=\"{}\";", dot_escape(table)).unwrap();
+        for person in persons {
+            writeln!(out, "    \"{}\";", dot_escape(person)).unwrap();
+        }
+        writeln!(out, "  }}").unwrap();
+    }
+    for person in tribe.persons().filter(|p| !tables.contains_key(p.as_str())) {
+        writeln!(out, "  \"{}\";", dot_escape(person)).unwrap();
+    }
+    for (p1, p2, strength) in tribe.relations() {
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [strength=\"{strength}\", color=\"{}\"];",
+            dot_escape(p1),
+            dot_escape(p2),
+            strength_color(strength),
+        )
+        .unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// The tribe in the GraphML format, with the table of the assignment as a node attribute.
+///
+/// The seed the assignment was solved with is kept as a graph attribute.
+pub fn to_graphml(tribe: &Tribe, assignment: Option<&Assignment>, seed: Option<u64>) -> String {
+    let tables = assignment.map(person_tables).unwrap_or_default();
+
+    // Writing to a String cannot fail
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"  <key id="strength" for="edge" attr.name="strength" attr.type="string"/>"#
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"  <key id="table" for="node" attr.name="table" attr.type="string"/>"#
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"  <key id="seed" for="graph" attr.name="seed" attr.type="long"/>"#
+    )
+    .unwrap();
+    writeln!(out, r#"  <graph id="tribe" edgedefault="directed">"#).unwrap();
+    if let Some(seed) = seed {
+        writeln!(out, r#"    <data key="seed">{seed}</data>"#).unwrap();
+    }
+    for person in tribe.persons() {
+        match tables.get(person.as_str()) {
+            Some(table) => writeln!(
+                out,
+                r#"    <node id="{}"><data key="table">{}</data></node>"#,
+                xml_escape(person),
+                xml_escape(table),
+            ),
+            None => writeln!(out, r#"    <node id="{}"/>"#, xml_escape(person)),
+        }
+        .unwrap();
+    }
+    for (p1, p2, strength) in tribe.relations() {
+        writeln!(
+            out,
+            r#"    <edge source="{}" target="{}"><data key="strength">{strength}</data></edge>"#,
+            xml_escape(p1),
+            xml_escape(p2),
+        )
+        .unwrap();
+    }
+    writeln!(out, "  </graph>").unwrap();
+    writeln!(out, "</graphml>").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tribe() -> Tribe {
+        let mut tribe = Tribe::new();
+        tribe.add_relation("Ron \"Won-Won\"", "Hermione", RelationStrength::Loves);
+        tribe.add_relation("Draco", "Ron \"Won-Won\"", RelationStrength::Hates);
+        tribe
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = to_dot(&tribe(), None, None);
+        assert!(dot.starts_with("digraph tribe {"));
+        assert!(dot.contains(r#""Hermione";"#));
+        assert!(
+            dot.contains(r#""Ron \"Won-Won\"" -> "Hermione" [strength="Loves", color="green"];"#)
+        );
+        assert!(!dot.contains("subgraph"));
+        assert!(!dot.contains("seed"));
+
+        let assignment = Assignment::from_iter([(
+            "Phoenix".to_owned(),
+            vec!["Draco".to_owned(), "Hermione".to_owned()],
+        )]);
+        let dot = to_dot(&tribe(), Some(&assignment), Some(42));
+        assert!(dot.contains("  seed=42;"));
+        assert!(dot.contains("subgraph cluster_0 {\n    label=\"Phoenix\";\n    \"Draco\";"));
+        // Persons are declared once, in their cluster if any
+        assert_eq!(dot.matches("  \"Hermione\";").count(), 1);
+    }
+
+    #[test]
+    fn test_to_graphml() {
+        let assignment =
+            Assignment::from_iter([("<Phoenix>".to_owned(), vec!["Draco".to_owned()])]);
+        let graphml = to_graphml(&tribe(), Some(&assignment), Some(42));
+        assert!(graphml.contains(r#"<data key="seed">42</data>"#));
+        assert!(
+            graphml.contains(r#"<node id="Draco"><data key="table">&lt;Phoenix&gt;</data></node>"#)
+        );
+        assert!(graphml.contains(r#"<node id="Hermione"/>"#));
+        assert!(graphml.contains(
+            r#"<edge source="Draco" target="Ron &quot;Won-Won&quot;"><data key="strength">Hates</data></edge>"#
+        ));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::logic::model::{PersonName, RelationStrength, Tribe};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("expected 3 fields (person, strength, person) but found {0}")]
+    FieldCount(usize),
+    #[error("unknown strength {0:?}, expected one of {expected}", expected = strength_names())]
+    UnknownStrength(String),
+    #[error("a person name is empty")]
+    EmptyName,
+    #[error("a person cannot have a relation with themselves")]
+    SamePerson,
+}
+
+fn strength_names() -> String {
+    RelationStrength::iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A relation parsed from a line of text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelationRow {
+    pub person1: PersonName,
+    pub strength: RelationStrength,
+    pub person2: PersonName,
+}
+
+/// The outcome of parsing one non-empty line, numbered from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedLine {
+    pub number: usize,
+    pub text: String,
+    pub row: Result<RelationRow, ParseError>,
+}
+
+/// Parse lines such as `Harry Potter, Loves, Ginny Weasley`.
+///
+/// Fields are separated by tabs, as copied from a spreadsheet, or else by commas or semicolons.
+/// Strengths are case insensitive and empty lines are skipped.
+pub fn parse_relations(text: &str) -> Vec<ParsedLine> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| ParsedLine {
+            number: i + 1,
+            text: line.to_owned(),
+            row: parse_relation(line),
+        })
+        .collect()
+}
+
+fn parse_relation(line: &str) -> Result<RelationRow, ParseError> {
+    let fields: Vec<&str> = if line.contains('\t') {
+        line.split('\t').map(str::trim).collect()
+    } else {
+        line.split([',', ';']).map(str::trim).collect()
+    };
+    let [person1, strength, person2] = fields.as_slice() else {
+        return Err(ParseError::FieldCount(fields.len()));
+    };
+    if person1.is_empty() || person2.is_empty() {
+        return Err(ParseError::EmptyName);
+    }
+    if person1 == person2 {
+        return Err(ParseError::SamePerson);
+    }
+    let strength = RelationStrength::iter()
+        .find(|s| s.to_string().eq_ignore_ascii_case(strength))
+        .ok_or_else(|| ParseError::UnknownStrength(strength.to_string()))?;
+    Ok(RelationRow {
+        person1: person1.to_string(),
+        strength,
+        person2: person2.to_string(),
+    })
+}
+
+/// Add the valid rows, creating missing persons, and return how many were added.
+pub fn add_relations<'a>(
+    tribe: &mut Tribe,
+    lines: impl IntoIterator<Item = &'a ParsedLine>,
+) -> usize {
+    let mut count = 0;
+    for row in lines.into_iter().filter_map(|l| l.row.as_ref().ok()) {
+        tribe.remove_relation(&row.person1, &row.person2);
+        tribe.add_relation(row.person1.clone(), row.person2.clone(), row.strength);
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_relations() {
+        let lines = parse_relations(
+            "Harry Potter, Loves, Ginny Weasley\n\
+             \n\
+             Ron Weasley\tlikes\tHermione Granger\n\
+             Draco Malfoy; Hates ;Harry Potter\n\
+             Luna Lovegood, Adores, Neville Longbottom\n\
+             Dobby, Likes\n\
+             , Likes, Dobby\n\
+             Dobby, Likes, Dobby",
+        );
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[0].row,
+            Ok(RelationRow {
+                person1: "Harry Potter".into(),
+                strength: RelationStrength::Loves,
+                person2: "Ginny Weasley".into(),
+            })
+        );
+        assert_eq!(lines[1].number, 3);
+        assert_eq!(
+            lines[1].row.as_ref().map(|r| r.strength),
+            Ok(RelationStrength::Likes)
+        );
+        assert_eq!(
+            lines[2].row.as_ref().map(|r| r.person1.as_str()),
+            Ok("Draco Malfoy")
+        );
+        assert_eq!(
+            lines[3].row,
+            Err(ParseError::UnknownStrength("Adores".into()))
+        );
+        assert_eq!(lines[4].row, Err(ParseError::FieldCount(2)));
+        assert_eq!(lines[5].row, Err(ParseError::EmptyName));
+        assert_eq!(lines[6].row, Err(ParseError::SamePerson));
+    }
+
+    #[test]
+    fn test_add_relations() {
+        let mut tribe = Tribe::new();
+        tribe.add_relation("A", "B", RelationStrength::Hates);
+
+        let lines = parse_relations("A, Loves, B\nB, Likes, C\nC, Meh, A");
+        assert_eq!(add_relations(&mut tribe, &lines), 2);
+        assert_eq!(tribe.persons_count(), 3);
+        assert_eq!(
+            tribe.directed_relation("A", "B"),
+            Some(RelationStrength::Loves)
+        );
+        assert_eq!(tribe.relations().count(), 2);
+    }
+}
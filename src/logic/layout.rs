@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use crate::logic::model::{Assignment, PersonName, RelationStrength, Tribe};
+
+/// A position in the unit square.
+pub type Position = (f32, f32);
+
+const MARGIN: f32 = 0.05;
+
+/// How much a relation pulls persons together, or pushes them apart when negative.
+fn relation_weight(strength: RelationStrength) -> f32 {
+    match strength {
+        RelationStrength::Hates => -0.6,
+        RelationStrength::Dislikes => -0.3,
+        RelationStrength::Likes => 0.6,
+        RelationStrength::Loves => 1.0,
+    }
+}
+
+/// Place persons with a force-directed layout, relations acting as springs.
+///
+/// When clusters are given, as the tables of an assignment, their members are also pulled
+/// together.
+/// The layout is deterministic, starting from persons evenly placed on circles.
+pub fn force_layout(
+    tribe: &Tribe,
+    clusters: Option<&Assignment>,
+    iterations: usize,
+) -> BTreeMap<PersonName, Position> {
+    let persons = tribe.persons().collect::<Vec<_>>();
+    let n_persons = persons.len();
+    if n_persons == 0 {
+        return BTreeMap::new();
+    }
+    let index = |name: &str| persons.binary_search_by(|p| p.as_str().cmp(name)).ok();
+
+    // Cluster of every person, if any
+    let mut cluster_of = vec![None; n_persons];
+    let mut n_clusters = 0;
+    for members in clusters.into_iter().flat_map(|c| c.values()) {
+        let members = members.iter().filter_map(|p| index(p)).collect::<Vec<_>>();
+        if !members.is_empty() {
+            for p in members {
+                cluster_of[p] = Some(n_clusters);
+            }
+            n_clusters += 1;
+        }
+    }
+
+    let on_circle = |i: usize, n: usize, center: Position, radius: f32| {
+        let angle = i as f32 * std::f32::consts::TAU / n.max(1) as f32;
+        (
+            center.0 + radius * angle.cos(),
+            center.1 + radius * angle.sin(),
+        )
+    };
+    let mut positions = (0..n_persons)
+        .map(|p| match cluster_of[p] {
+            Some(c) => {
+                let center = on_circle(c, n_clusters, (0.5, 0.5), 0.3);
+                on_circle(p, n_persons, center, 0.05)
+            }
+            None => on_circle(p, n_persons, (0.5, 0.5), 0.4),
+        })
+        .collect::<Vec<_>>();
+
+    let edges = tribe
+        .relations()
+        .filter_map(|(p1, p2, s)| Some((index(p1)?, index(p2)?, relation_weight(s))))
+        .collect::<Vec<_>>();
+
+    // Ideal distance between persons
+    let k = 0.5 * (1.0 / n_persons as f32).sqrt();
+    let mut displacements = vec![(0.0_f32, 0.0_f32); n_persons];
+    for iteration in 0..iterations {
+        displacements.fill((0.0, 0.0));
+        let mut push = |p: usize, (dx, dy): Position, force: f32, d: f32| {
+            displacements[p].0 += dx / d * force;
+            displacements[p].1 += dy / d * force;
+        };
+        let delta = |p1: usize, p2: usize| {
+            let (dx, dy) = (
+                positions[p1].0 - positions[p2].0,
+                positions[p1].1 - positions[p2].1,
+            );
+            (dx, dy, (dx * dx + dy * dy).sqrt().max(1e-3))
+        };
+
+        for p1 in 0..n_persons {
+            for p2 in (p1 + 1)..n_persons {
+                let (dx, dy, d) = delta(p1, p2);
+                let force = k * k / d;
+                push(p1, (dx, dy), force, d);
+                push(p2, (-dx, -dy), force, d);
+            }
+        }
+        for &(p1, p2, weight) in edges.iter() {
+            let (dx, dy, d) = delta(p1, p2);
+            let force = match weight > 0.0 {
+                true => -weight * d * d / k,
+                false => -weight * k * k / d,
+            };
+            push(p1, (dx, dy), force, d);
+            push(p2, (-dx, -dy), force, d);
+        }
+        if n_clusters > 0 {
+            let mut centers = vec![(0.0_f32, 0.0_f32, 0_usize); n_clusters];
+            for (p, c) in cluster_of.iter().enumerate() {
+                if let Some(c) = c {
+                    centers[*c].0 += positions[p].0;
+                    centers[*c].1 += positions[p].1;
+                    centers[*c].2 += 1;
+                }
+            }
+            for (p, c) in cluster_of.iter().enumerate() {
+                if let Some(c) = c {
+                    let (x, y, n) = centers[*c];
+                    let (dx, dy) = (positions[p].0 - x / n as f32, positions[p].1 - y / n as f32);
+                    let d = (dx * dx + dy * dy).sqrt().max(1e-3);
+                    push(p, (dx, dy), -2.0 * d * d / k, d);
+                }
+            }
+        }
+
+        // Cool down so that the layout settles
+        let temperature = 0.1 * (1.0 - iteration as f32 / iterations as f32);
+        for (position, (dx, dy)) in positions.iter_mut().zip(displacements.iter()) {
+            let d = (dx * dx + dy * dy).sqrt().max(1e-6);
+            let step = d.min(temperature);
+            position.0 = (position.0 + dx / d * step).clamp(MARGIN, 1.0 - MARGIN);
+            position.1 = (position.1 + dy / d * step).clamp(MARGIN, 1.0 - MARGIN);
+        }
+    }
+
+    persons.into_iter().cloned().zip(positions).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::examples;
+
+    fn distance(layout: &BTreeMap<PersonName, Position>, p1: &str, p2: &str) -> f32 {
+        let (a, b) = (layout[p1], layout[p2]);
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_force_layout() {
+        assert!(force_layout(&Tribe::new(), None, 10).is_empty());
+
+        let mut tribe = Tribe::new();
+        tribe.add_relation("A", "B", RelationStrength::Loves);
+        tribe.add_relation("C", "D", RelationStrength::Loves);
+        tribe.add_relation("A", "C", RelationStrength::Hates);
+
+        let layout = force_layout(&tribe, None, 200);
+        assert_eq!(layout.len(), 4);
+        assert_eq!(layout, force_layout(&tribe, None, 200));
+        for (x, y) in layout.values() {
+            assert!((MARGIN..=1.0 - MARGIN).contains(x));
+            assert!((MARGIN..=1.0 - MARGIN).contains(y));
+        }
+        assert!(distance(&layout, "A", "B") < distance(&layout, "A", "C"));
+        assert!(distance(&layout, "C", "D") < distance(&layout, "A", "C"));
+    }
+
+    #[test]
+    fn test_force_layout_clusters() {
+        let (tribe, _) = examples::harry_potter();
+        let persons = tribe.persons().cloned().collect::<Vec<_>>();
+        let (first, second) = persons.split_at(persons.len() / 2);
+        let assignment = Assignment::from_iter([
+            ("1".to_owned(), first.to_vec()),
+            ("2".to_owned(), second.to_vec()),
+        ]);
+
+        let layout = force_layout(&tribe, Some(&assignment), 200);
+        let mean = |pairs: Vec<(&PersonName, &PersonName)>| {
+            let n = pairs.len() as f32;
+            pairs
+                .into_iter()
+                .map(|(p1, p2)| distance(&layout, p1, p2))
+                .sum::<f32>()
+                / n
+        };
+        let within = mean(
+            first
+                .iter()
+                .flat_map(|p1| first.iter().map(move |p2| (p1, p2)))
+                .collect(),
+        );
+        let across = mean(
+            first
+                .iter()
+                .flat_map(|p1| second.iter().map(move |p2| (p1, p2)))
+                .collect(),
+        );
+        assert!(within < across);
+    }
+}
@@ -1,3 +1,8 @@
 pub mod examples;
+pub mod export;
+pub mod import;
+pub mod layout;
 pub mod model;
+pub mod monitor;
+pub mod random;
 pub mod solver;
@@ -1,6 +1,8 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-#[derive(Clone, Copy, Debug, strum::Display, strum::EnumIter, strum::FromRepr)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display, strum::EnumIter, strum::FromRepr,
+)]
 pub enum RelationStrength {
     Hates,
     Dislikes,
@@ -21,6 +23,14 @@ impl RelationStrength {
         <Self as strum::IntoEnumIterator>::iter()
     }
 
+    /// The strength after the given one, cycling through no relation after the strongest.
+    pub fn cycle(strength: Option<Self>) -> Option<Self> {
+        match strength {
+            None => Some(Self::min()),
+            Some(s) => Self::from_repr(s as usize + 1),
+        }
+    }
+
     pub const fn len() -> usize {
         RelationStrength::Loves as usize + 1
         // TODO exerimental
@@ -31,15 +41,153 @@ impl RelationStrength {
 pub type PersonName = String;
 pub type PersonNameRef = str;
 
-#[derive(Default)]
+/// The answer of a person to the invitation.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+pub enum Rsvp {
+    #[default]
+    Confirmed,
+    Tentative,
+    Declined,
+}
+
+impl Rsvp {
+    pub fn iter() -> impl Clone + DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+        <Self as strum::IntoEnumIterator>::iter()
+    }
+}
+
+/// How strongly the members of a group should be seated at the same table.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    strum::FromRepr,
+    strum::EnumString,
+)]
+pub enum GroupCohesion {
+    /// Members may be seated anywhere.
+    #[default]
+    Free,
+    /// Splitting members across tables is penalized.
+    Prefer,
+    /// Members must all be seated at the same table.
+    Require,
+}
+
+impl GroupCohesion {
+    pub fn iter() -> impl Clone + DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+        <Self as strum::IntoEnumIterator>::iter()
+    }
+}
+
+pub type GroupName = String;
+pub type GroupNameRef = str;
+
+/// A named set of persons, such as a household or a party coming together.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Group {
+    members: BTreeSet<PersonName>,
+    /// Relation applied between members that have no explicit relation.
+    pub relation: Option<RelationStrength>,
+    pub cohesion: GroupCohesion,
+}
+
+impl Group {
+    pub fn members_count(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = &PersonName> {
+        self.members.iter()
+    }
+
+    pub fn contains(&self, name: &PersonNameRef) -> bool {
+        self.members.contains(name)
+    }
+}
+
+pub type AttributeName = String;
+pub type AttributeNameRef = str;
+pub type AttributeValue = String;
+
+/// A tag on a person, such as a spoken language or a side of the family.
+///
+/// A person may have several values for the same attribute name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Attribute {
+    pub name: AttributeName,
+    pub value: AttributeValue,
+}
+
+impl Attribute {
+    pub fn new(name: impl Into<AttributeName>, value: impl Into<AttributeValue>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Attribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+/// A target on the persons seated at every table, based on their attributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttributeGoal {
+    /// Every table seats at least `count` persons with the attribute.
+    AtLeast { attribute: Attribute, count: u32 },
+    /// Every table seats persons with all the values of the attribute.
+    Mix { name: AttributeName },
+}
+
+impl std::fmt::Display for AttributeGoal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AtLeast { attribute, count } => write!(f, "At least {count} {attribute}"),
+            Self::Mix { name } => write!(f, "Mix all {name}"),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct Tribe {
     directed_relations: BTreeMap<PersonName, HashMap<PersonName, RelationStrength>>,
+    groups: BTreeMap<GroupName, Group>,
+    attributes: BTreeMap<PersonName, BTreeSet<Attribute>>,
+    /// Persons kept with their relations but not seated.
+    absent: BTreeSet<PersonName>,
+    /// Answers other than the default one.
+    rsvps: BTreeMap<PersonName, Rsvp>,
 }
 
 impl Tribe {
     pub fn new() -> Self {
         Self {
             directed_relations: BTreeMap::new(),
+            groups: BTreeMap::new(),
+            attributes: BTreeMap::new(),
+            absent: BTreeSet::new(),
+            rsvps: BTreeMap::new(),
         }
     }
 
@@ -52,6 +200,69 @@ impl Tribe {
             neighbors.remove(name);
         }
         self.directed_relations.remove(name);
+        for group in self.groups.values_mut() {
+            group.members.remove(name);
+        }
+        self.attributes.remove(name);
+        self.absent.remove(name);
+        self.rsvps.remove(name);
+    }
+
+    pub fn set_absent(&mut self, name: &PersonNameRef, absent: bool) {
+        if !self.contains_person(name) {
+            return;
+        }
+        if absent {
+            self.absent.insert(name.to_owned());
+        } else {
+            self.absent.remove(name);
+        }
+    }
+
+    pub fn is_absent(&self, name: &PersonNameRef) -> bool {
+        self.absent.contains(name)
+    }
+
+    pub fn absent_count(&self) -> usize {
+        self.absent.len()
+    }
+
+    pub fn set_rsvp(&mut self, name: &PersonNameRef, rsvp: Rsvp) {
+        if !self.contains_person(name) {
+            return;
+        }
+        if rsvp == Rsvp::default() {
+            self.rsvps.remove(name);
+        } else {
+            self.rsvps.insert(name.to_owned(), rsvp);
+        }
+    }
+
+    pub fn rsvp(&self, name: &PersonNameRef) -> Rsvp {
+        self.rsvps.get(name).copied().unwrap_or_default()
+    }
+
+    /// The number of persons with the given answer.
+    pub fn rsvp_count(&self, rsvp: Rsvp) -> usize {
+        self.persons().filter(|p| self.rsvp(p) == rsvp).count()
+    }
+
+    /// The same tribe with only the persons to seat, that is present and coming.
+    pub fn attending(&self, include_tentative: bool) -> Self {
+        let mut tribe = self.clone();
+        let not_coming = self.persons().filter(|p| match self.rsvp(p) {
+            Rsvp::Confirmed => false,
+            Rsvp::Tentative => !include_tentative,
+            Rsvp::Declined => true,
+        });
+        for name in self.absent.iter().chain(not_coming) {
+            tribe.remove_person(name);
+        }
+        tribe
+    }
+
+    pub fn contains_person(&self, name: &PersonNameRef) -> bool {
+        self.directed_relations.contains_key(name)
     }
 
     pub fn persons_count(&self) -> usize {
@@ -90,6 +301,156 @@ impl Tribe {
                 .map(move |(p2, strength)| (p1, p2, *strength))
         })
     }
+
+    /// Relations set by or toward a person.
+    pub fn person_relations<'a>(
+        &'a self,
+        name: &'a PersonNameRef,
+    ) -> impl Iterator<Item = (&'a PersonName, &'a PersonName, RelationStrength)> {
+        self.relations()
+            .filter(move |(p1, p2, _)| *p1 == name || *p2 == name)
+    }
+
+    /// The relation set by the first person toward the second, if any.
+    pub fn directed_relation(
+        &self,
+        name1: &PersonNameRef,
+        name2: &PersonNameRef,
+    ) -> Option<RelationStrength> {
+        self.directed_relations
+            .get(name1)
+            .and_then(|neighbors| neighbors.get(name2))
+            .copied()
+    }
+
+    /// The relation set by either person toward the other, if any.
+    pub fn relation(
+        &self,
+        name1: &PersonNameRef,
+        name2: &PersonNameRef,
+    ) -> Option<RelationStrength> {
+        self.directed_relation(name1, name2)
+            .or_else(|| self.directed_relation(name2, name1))
+    }
+
+    /// Relations implied by groups between members without an explicit relation.
+    pub fn group_relations(
+        &self,
+    ) -> impl Iterator<Item = (&PersonName, &PersonName, RelationStrength)> {
+        self.groups.values().flat_map(move |group| {
+            let members = &group.members;
+            group.relation.into_iter().flat_map(move |strength| {
+                members.iter().enumerate().flat_map(move |(i, p1)| {
+                    members
+                        .iter()
+                        .skip(i + 1)
+                        .filter(move |p2| self.relation(p1, p2).is_none())
+                        .map(move |p2| (p1, p2, strength))
+                })
+            })
+        })
+    }
+
+    pub fn groups_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&GroupName, &Group)> {
+        self.groups.iter()
+    }
+
+    pub fn group(&self, name: &GroupNameRef) -> Option<&Group> {
+        self.groups.get(name)
+    }
+
+    /// The group a person belongs to, persons belong to at most one group.
+    pub fn person_group(&self, person: &PersonNameRef) -> Option<&GroupName> {
+        self.groups
+            .iter()
+            .find(|(_, group)| group.contains(person))
+            .map(|(name, _)| name)
+    }
+
+    /// Add a group, or update the settings of an existing one, keeping its members.
+    pub fn add_group(
+        &mut self,
+        name: impl Into<GroupName>,
+        relation: Option<RelationStrength>,
+        cohesion: GroupCohesion,
+    ) {
+        let group = self.groups.entry(name.into()).or_default();
+        group.relation = relation;
+        group.cohesion = cohesion;
+    }
+
+    /// Remove a group, its members are kept in the tribe.
+    pub fn remove_group(&mut self, name: &GroupNameRef) {
+        self.groups.remove(name);
+    }
+
+    /// Add a person to a group, removing them from any other group.
+    pub fn add_to_group(&mut self, group: impl Into<GroupName>, person: impl Into<PersonName>) {
+        let person = person.into();
+        self.directed_relations.entry(person.clone()).or_default();
+        for group in self.groups.values_mut() {
+            group.members.remove(&person);
+        }
+        self.groups
+            .entry(group.into())
+            .or_default()
+            .members
+            .insert(person);
+    }
+
+    pub fn remove_from_group(&mut self, group: &GroupNameRef, person: &PersonNameRef) {
+        if let Some(group) = self.groups.get_mut(group) {
+            group.members.remove(person);
+        }
+    }
+
+    pub fn add_attribute(&mut self, person: impl Into<PersonName>, attribute: Attribute) {
+        let person = person.into();
+        self.directed_relations.entry(person.clone()).or_default();
+        self.attributes.entry(person).or_default().insert(attribute);
+    }
+
+    pub fn remove_attribute(&mut self, person: &PersonNameRef, attribute: &Attribute) {
+        if let Some(attributes) = self.attributes.get_mut(person) {
+            attributes.remove(attribute);
+            if attributes.is_empty() {
+                self.attributes.remove(person);
+            }
+        }
+    }
+
+    pub fn has_attribute(&self, person: &PersonNameRef, attribute: &Attribute) -> bool {
+        self.attributes
+            .get(person)
+            .is_some_and(|attributes| attributes.contains(attribute))
+    }
+
+    pub fn person_attributes(&self, person: &PersonNameRef) -> impl Iterator<Item = &Attribute> {
+        self.attributes.get(person).into_iter().flatten()
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = (&PersonName, &Attribute)> {
+        self.attributes
+            .iter()
+            .flat_map(|(p, attributes)| attributes.iter().map(move |a| (p, a)))
+    }
+
+    /// All the distinct attribute names used in the tribe.
+    pub fn attribute_names(&self) -> BTreeSet<&AttributeName> {
+        self.attributes().map(|(_, a)| &a.name).collect()
+    }
+
+    /// All the distinct values used for the given attribute name.
+    pub fn attribute_values(&self, name: &AttributeNameRef) -> BTreeSet<&AttributeValue> {
+        self.attributes()
+            .filter(|(_, a)| a.name == name)
+            .map(|(_, a)| &a.value)
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -104,6 +465,62 @@ pub type Tables = BTreeMap<TableName, TableType>;
 
 pub type Assignment = BTreeMap<TableName, Vec<PersonName>>;
 
+/// The table at which some persons must be seated.
+pub type Pins = BTreeMap<PersonName, TableName>;
+
+/// Move a person to another table, returning whether the person was found.
+pub fn move_person(
+    assignment: &mut Assignment,
+    person: &PersonNameRef,
+    table: &TableNameRef,
+) -> bool {
+    if !assignment.contains_key(table) {
+        return false;
+    }
+    let found = assignment.values_mut().any(|persons| {
+        let len = persons.len();
+        persons.retain(|p| p != person);
+        persons.len() != len
+    });
+    if found {
+        assignment.get_mut(table).unwrap().push(person.to_owned());
+    }
+    found
+}
+
+/// Exchange the tables of two persons, returning whether both were found.
+pub fn swap_persons(
+    assignment: &mut Assignment,
+    person1: &PersonNameRef,
+    person2: &PersonNameRef,
+) -> bool {
+    let mut seats = assignment
+        .values_mut()
+        .flat_map(|persons| persons.iter_mut())
+        .filter(|p| *p == person1 || *p == person2)
+        .collect::<Vec<_>>();
+    if let [seat1, seat2] = seats.as_mut_slice() {
+        std::mem::swap(*seat1, *seat2);
+        true
+    } else {
+        false
+    }
+}
+
+/// Persons seated in both assignments, but at different tables.
+pub fn moved_persons(before: &Assignment, after: &Assignment) -> BTreeSet<PersonName> {
+    let tables_before = before
+        .iter()
+        .flat_map(|(t, persons)| persons.iter().map(move |p| (p, t)))
+        .collect::<BTreeMap<_, _>>();
+    after
+        .iter()
+        .flat_map(|(t, persons)| persons.iter().map(move |p| (p, t)))
+        .filter(|(p, t)| tables_before.get(p).is_some_and(|before| before != t))
+        .map(|(p, _)| p.clone())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -119,6 +536,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_relationship_strength_cycle() {
+        let cycle = std::iter::successors(Some(None), |s| Some(RelationStrength::cycle(*s)))
+            .skip(1)
+            .take(RelationStrength::len() + 1)
+            .collect::<Vec<_>>();
+        assert_eq!(cycle.first(), Some(&Some(RelationStrength::min())));
+        assert_eq!(
+            cycle[RelationStrength::len() - 1],
+            Some(RelationStrength::max())
+        );
+        assert_eq!(cycle.last(), Some(&None));
+    }
+
     #[test]
     fn test_tribe() {
         let mut tribe = Tribe::new();
@@ -148,4 +579,171 @@ mod tests {
         );
         assert_eq!(tribe.relations().count(), 2);
     }
+
+    #[test]
+    fn test_tribe_directed_relations() {
+        let mut tribe = Tribe::new();
+        tribe.add_relation("Antoine", "Mathieu", RelationStrength::Likes);
+        assert_eq!(
+            tribe.directed_relation("Antoine", "Mathieu"),
+            Some(RelationStrength::Likes)
+        );
+        assert_eq!(tribe.directed_relation("Mathieu", "Antoine"), None);
+        assert_eq!(
+            tribe.relation("Mathieu", "Antoine"),
+            Some(RelationStrength::Likes)
+        );
+    }
+
+    #[test]
+    fn test_tribe_groups() {
+        let mut tribe = Tribe::new();
+        tribe.add_relation("Antoine", "Mathieu", RelationStrength::Likes);
+        tribe.add_group(
+            "Family",
+            Some(RelationStrength::Loves),
+            GroupCohesion::Prefer,
+        );
+        assert_eq!(tribe.groups_count(), 1);
+        assert_eq!(tribe.group_relations().count(), 0);
+
+        tribe.add_to_group("Family", "Antoine");
+        tribe.add_to_group("Family", "Mathieu");
+        tribe.add_to_group("Family", "Charles");
+        assert_eq!(tribe.persons_count(), 3);
+        assert_eq!(tribe.group("Family").unwrap().members_count(), 3);
+        assert_eq!(tribe.person_group("Charles").unwrap(), "Family");
+        // The explicit relation between Antoine and Mathieu takes precedence
+        assert_eq!(
+            tribe
+                .group_relations()
+                .map(|(p1, p2, _)| (p1.as_str(), p2.as_str()))
+                .collect::<HashSet<_>>(),
+            HashSet::from([("Antoine", "Charles"), ("Charles", "Mathieu")]),
+        );
+
+        // Persons belong to a single group
+        tribe.add_to_group("Friends", "Charles");
+        assert_eq!(tribe.groups_count(), 2);
+        assert_eq!(tribe.group("Family").unwrap().members_count(), 2);
+        assert_eq!(tribe.person_group("Charles").unwrap(), "Friends");
+
+        tribe.remove_person("Antoine");
+        assert_eq!(tribe.group("Family").unwrap().members_count(), 1);
+
+        tribe.remove_group("Family");
+        assert_eq!(tribe.groups_count(), 1);
+        assert_eq!(tribe.persons_count(), 2);
+    }
+
+    #[test]
+    fn test_tribe_attributes() {
+        let french = Attribute::new("Language", "French");
+        let english = Attribute::new("Language", "English");
+
+        let mut tribe = Tribe::new();
+        tribe.add_attribute("Antoine", french.clone());
+        tribe.add_attribute("Antoine", english.clone());
+        tribe.add_attribute("Mathieu", french.clone());
+        tribe.add_attribute("Mathieu", Attribute::new("Side", "Groom"));
+        assert_eq!(tribe.persons_count(), 2);
+        assert_eq!(tribe.attributes().count(), 4);
+        assert!(tribe.has_attribute("Antoine", &english));
+        assert!(!tribe.has_attribute("Mathieu", &english));
+        assert_eq!(tribe.person_attributes("Antoine").count(), 2);
+        assert_eq!(tribe.attribute_names().len(), 2);
+        assert_eq!(tribe.attribute_values("Language").len(), 2);
+
+        tribe.remove_attribute("Antoine", &english);
+        assert_eq!(tribe.attribute_values("Language").len(), 1);
+
+        tribe.remove_person("Mathieu");
+        assert_eq!(tribe.attributes().count(), 1);
+        assert_eq!(tribe.attribute_names().len(), 1);
+    }
+
+    #[test]
+    fn test_moved_persons() {
+        let before = Assignment::from_iter([
+            ("Oak".to_owned(), vec!["A".to_owned(), "B".to_owned()]),
+            ("Elm".to_owned(), vec!["C".to_owned()]),
+        ]);
+        let after = Assignment::from_iter([
+            ("Oak".to_owned(), vec!["B".to_owned(), "C".to_owned()]),
+            ("Elm".to_owned(), vec!["D".to_owned()]),
+        ]);
+        assert_eq!(
+            moved_persons(&before, &after),
+            BTreeSet::from(["C".to_owned()])
+        );
+        assert!(moved_persons(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_assignment_edits() {
+        let mut assignment = Assignment::from_iter([
+            ("Oak".to_owned(), vec!["A".to_owned(), "B".to_owned()]),
+            ("Elm".to_owned(), vec!["C".to_owned()]),
+        ]);
+
+        assert!(move_person(&mut assignment, "A", "Elm"));
+        assert_eq!(assignment["Oak"], vec!["B"]);
+        assert_eq!(assignment["Elm"], vec!["C", "A"]);
+        assert!(!move_person(&mut assignment, "D", "Elm"));
+        assert!(!move_person(&mut assignment, "A", "Pine"));
+
+        assert!(swap_persons(&mut assignment, "B", "C"));
+        assert_eq!(assignment["Oak"], vec!["C"]);
+        assert_eq!(assignment["Elm"], vec!["B", "A"]);
+        assert!(!swap_persons(&mut assignment, "B", "D"));
+    }
+
+    #[test]
+    fn test_tribe_absent() {
+        let mut tribe = Tribe::new();
+        tribe.add_relation("A", "B", RelationStrength::Likes);
+        tribe.add_relation("C", "A", RelationStrength::Dislikes);
+        tribe.add_relation("B", "C", RelationStrength::Loves);
+        assert_eq!(tribe.person_relations("A").count(), 2);
+
+        tribe.set_absent("A", true);
+        tribe.set_absent("D", true);
+        assert!(tribe.is_absent("A"));
+        assert_eq!(tribe.absent_count(), 1);
+        // Relations are kept for later
+        assert_eq!(tribe.relations().count(), 3);
+
+        let attending = tribe.attending(true);
+        assert!(!attending.contains_person("A"));
+        assert_eq!(attending.relations().count(), 1);
+
+        tribe.set_absent("A", false);
+        assert_eq!(tribe.attending(true).persons_count(), 3);
+        tribe.set_absent("A", true);
+        tribe.remove_person("A");
+        assert_eq!(tribe.absent_count(), 0);
+    }
+
+    #[test]
+    fn test_tribe_rsvp() {
+        let mut tribe = Tribe::new();
+        for p in ["A", "B", "C", "D"] {
+            tribe.add_person(p);
+        }
+        tribe.set_rsvp("B", Rsvp::Tentative);
+        tribe.set_rsvp("C", Rsvp::Declined);
+        tribe.set_rsvp("E", Rsvp::Declined);
+        assert_eq!(tribe.rsvp("A"), Rsvp::Confirmed);
+        assert_eq!(tribe.rsvp("B"), Rsvp::Tentative);
+        assert_eq!(tribe.rsvp_count(Rsvp::Confirmed), 2);
+        assert_eq!(tribe.rsvp_count(Rsvp::Declined), 1);
+
+        let attending = tribe.attending(true);
+        assert_eq!(attending.persons().collect::<Vec<_>>(), ["A", "B", "D"]);
+        let attending = tribe.attending(false);
+        assert_eq!(attending.persons().collect::<Vec<_>>(), ["A", "D"]);
+
+        tribe.set_rsvp("C", Rsvp::Confirmed);
+        assert_eq!(tribe.attending(false).persons_count(), 3);
+    }
 }
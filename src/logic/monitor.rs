@@ -0,0 +1,334 @@
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::solver::Cost;
+
+/// Measure elapsed time, also in browsers where [`std::time::Instant`] is not available.
+#[derive(Clone, Copy, Debug)]
+pub struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    start_ms: f64,
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+            #[cfg(target_arch = "wasm32")]
+            start_ms: js_sys::Date::now(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.start.elapsed();
+        #[cfg(target_arch = "wasm32")]
+        return Duration::from_secs_f64((js_sys::Date::now() - self.start_ms).max(0.0) / 1000.0);
+    }
+}
+
+/// Ask a running solve to stop, shared with the thread running it.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A snapshot of a running solve.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Progress {
+    /// The round being solved, from zero.
+    pub round: usize,
+    /// The number of candidate assignments evaluated so far.
+    pub iteration: u64,
+    pub elapsed: Duration,
+    /// The value of the [`Objective`] for the best assignment found so far in the round, over
+    /// all starts: its total cost, the cost of its worst-off person, or a blend of both.
+    ///
+    /// [`Objective`]: super::solver::Objective
+    pub objective: Cost,
+}
+
+type ProgressCallback<'a> = Box<dyn FnMut(&Progress) + Send + 'a>;
+
+/// Follow and stop a running solve.
+pub struct Monitor<'a> {
+    stopwatch: Stopwatch,
+    cancel: CancelToken,
+    on_progress: Option<ProgressCallback<'a>>,
+    report_every: Duration,
+    last_report: Duration,
+    deadline: Option<Duration>,
+    iteration_limit: Option<u64>,
+    round: usize,
+    iteration: u64,
+    /// The best objective reported in the round, as starts begin again from worse ones.
+    best: Cost,
+    #[cfg(target_arch = "wasm32")]
+    last_pause: Duration,
+}
+
+impl Default for Monitor<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Monitor<'a> {
+    /// Checking the time is not free, so it is only done every so many iterations.
+    const CHECK_EVERY: u64 = 256;
+    /// How long a solve may keep the browser busy, about one frame.
+    #[cfg(target_arch = "wasm32")]
+    const PAUSE_EVERY: Duration = Duration::from_millis(16);
+
+    pub fn new() -> Self {
+        Self {
+            stopwatch: Stopwatch::start(),
+            cancel: CancelToken::new(),
+            on_progress: None,
+            report_every: Duration::from_millis(100),
+            last_report: Duration::ZERO,
+            deadline: None,
+            iteration_limit: None,
+            round: 0,
+            iteration: 0,
+            best: Cost::INFINITY,
+            #[cfg(target_arch = "wasm32")]
+            last_pause: Duration::ZERO,
+        }
+    }
+
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Call a function with the progress, at most at the given interval while solving.
+    pub fn with_progress(
+        mut self,
+        report_every: Duration,
+        on_progress: impl FnMut(&Progress) + Send + 'a,
+    ) -> Self {
+        self.report_every = report_every;
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.stopwatch.elapsed()
+    }
+
+    pub fn iteration(&self) -> u64 {
+        self.iteration
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// Time since the start after which to stop searching, if any.
+    pub(crate) fn set_deadline(&mut self, deadline: Option<Duration>) {
+        self.deadline = deadline;
+    }
+
+    pub fn iteration_limit(&self) -> Option<u64> {
+        self.iteration_limit
+    }
+
+    /// Number of iterations since the start after which to stop searching, if any.
+    pub(crate) fn set_iteration_limit(&mut self, limit: Option<u64>) {
+        self.iteration_limit = limit;
+    }
+
+    /// Whether to keep searching until a deadline or iteration limit, rather than stopping at
+    /// the first assignment that cannot be improved by a single change.
+    pub fn is_limited(&self) -> bool {
+        self.deadline.is_some() || self.iteration_limit.is_some()
+    }
+
+    /// Whether searching should stop, because cancelled or out of time or iterations.
+    pub fn is_stopped(&self) -> bool {
+        self.is_cancelled()
+            || self.iteration_limit.is_some_and(|l| self.iteration >= l)
+            || self.deadline.is_some_and(|d| self.elapsed() >= d)
+    }
+
+    /// A monitor for another thread, sharing the clock, cancellation and limits, and sending
+    /// its progress along with the given worker number to be followed by
+    /// [`Monitor::follow_workers`].
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub(crate) fn worker(
+        &self,
+        worker: usize,
+        progress: &mpsc::Sender<(usize, Progress)>,
+    ) -> Monitor<'static> {
+        let on_progress = self.on_progress.is_some().then(|| {
+            let progress = progress.clone();
+            Box::new(move |update: &Progress| {
+                // The receiver is only gone once the workers are done
+                let _ = progress.send((worker, update.clone()));
+            }) as ProgressCallback<'static>
+        });
+        Monitor {
+            stopwatch: self.stopwatch,
+            cancel: self.cancel.clone(),
+            on_progress,
+            report_every: self.report_every,
+            last_report: self.last_report,
+            deadline: self.deadline,
+            iteration_limit: self.iteration_limit,
+            round: self.round,
+            iteration: self.iteration,
+            best: self.best,
+        }
+    }
+
+    /// Report the progress sent by workers until they are all done, counting the iterations
+    /// every one of them made since it was created.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub(crate) fn follow_workers(&mut self, progress: mpsc::Receiver<(usize, Progress)>) {
+        let mut iterations = HashMap::new();
+        for (worker, update) in progress {
+            iterations.insert(worker, update.iteration - self.iteration);
+            self.best = self.best.min(update.objective);
+            let elapsed = self.elapsed();
+            if elapsed >= self.last_report + self.report_every {
+                let iteration = self.iteration + iterations.values().sum::<u64>();
+                self.report_at(elapsed, iteration, self.best);
+            }
+        }
+    }
+
+    /// Count iterations done elsewhere, such as by workers.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub(crate) fn add_iterations(&mut self, iterations: u64) {
+        self.iteration += iterations;
+    }
+
+    /// Let the browser handle events if the solve kept it busy for long, since it runs on its
+    /// only thread. Cancelling and showing progress only happen while paused there.
+    ///
+    /// Elsewhere the solve runs in its own thread and this does nothing.
+    pub(crate) async fn pause(&mut self) {
+        #[cfg(target_arch = "wasm32")]
+        if self.elapsed() >= self.last_pause + Self::PAUSE_EVERY {
+            gloo_timers::future::TimeoutFuture::new(0).await;
+            self.last_pause = self.elapsed();
+        }
+    }
+
+    pub(crate) fn start_round(&mut self, round: usize) {
+        self.round = round;
+        self.best = Cost::INFINITY;
+    }
+
+    /// Count one evaluated assignment, reporting progress now and then.
+    pub(crate) fn step(&mut self, objective: Cost) {
+        self.iteration += 1;
+        if self.on_progress.is_some() && self.iteration.is_multiple_of(Self::CHECK_EVERY) {
+            let elapsed = self.elapsed();
+            if elapsed >= self.last_report + self.report_every {
+                self.report_at(elapsed, self.iteration, objective);
+            }
+        }
+    }
+
+    /// Report progress regardless of when it was last done.
+    pub(crate) fn report(&mut self, objective: Cost) {
+        if self.on_progress.is_some() {
+            self.report_at(self.elapsed(), self.iteration, objective);
+        }
+    }
+
+    fn report_at(&mut self, elapsed: Duration, iteration: u64, objective: Cost) {
+        self.last_report = elapsed;
+        self.best = self.best.min(objective);
+        let progress = Progress {
+            round: self.round,
+            iteration,
+            elapsed,
+            objective: self.best,
+        };
+        if let Some(on_progress) = &mut self.on_progress {
+            on_progress(&progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor() {
+        let cancel = CancelToken::new();
+        let mut reports = Vec::new();
+        {
+            let mut monitor = Monitor::new()
+                .with_cancel(cancel.clone())
+                .with_progress(Duration::ZERO, |p| reports.push(p.clone()));
+            monitor.start_round(1);
+            for _ in 0..Monitor::CHECK_EVERY {
+                monitor.step(3.0);
+            }
+            monitor.report(2.0);
+            assert_eq!(monitor.iteration(), Monitor::CHECK_EVERY);
+            // A new start may be worse than the best of the round so far
+            monitor.report(5.0);
+            monitor.start_round(2);
+            monitor.report(5.0);
+
+            assert!(!monitor.is_stopped());
+            monitor.set_deadline(Some(Duration::ZERO));
+            assert!(monitor.is_stopped());
+            monitor.set_deadline(None);
+            assert!(!monitor.is_stopped());
+            monitor.set_iteration_limit(Some(Monitor::CHECK_EVERY));
+            assert!(monitor.is_stopped());
+            monitor.set_iteration_limit(None);
+            assert!(!monitor.is_limited());
+
+            cancel.cancel();
+            assert!(monitor.is_cancelled());
+            assert!(monitor.is_stopped());
+        }
+
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports[0].round, 1);
+        assert_eq!(reports[0].iteration, Monitor::CHECK_EVERY);
+        assert_eq!(reports[0].objective, 3.0);
+        assert_eq!(reports[1].objective, 2.0);
+        assert_eq!(reports[2].objective, 2.0);
+        assert_eq!(reports[3].round, 2);
+        assert_eq!(reports[3].objective, 5.0);
+    }
+}
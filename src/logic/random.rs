@@ -0,0 +1,73 @@
+/// A small and fast pseudo-random generator (SplitMix64), reproducible from its seed.
+///
+/// Not suitable for anything related to security.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// An independent generator for the given stream, reproducible from the seed of this one.
+    pub fn split(&self, stream: u64) -> Self {
+        Self::new(self.state ^ Self::new(stream).next_u64())
+    }
+
+    /// A number in `0..n`, with `n` greater than zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0);
+        // The modulo bias is negligible for the small ranges used here
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A random element of the slice, if not empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        (!items.is_empty()).then(|| &items[self.below(items.len())])
+    }
+
+    /// Shuffle in place with the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let draws = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..10).map(|_| rng.below(100)).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+        assert!(draws(7).iter().all(|x| *x < 100));
+
+        let mut rng = Rng::new(0);
+        let mut items = (0..20).collect::<Vec<_>>();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+        assert_eq!(rng.choose::<u8>(&[]), None);
+
+        let base = Rng::new(3);
+        assert_eq!(base.split(1).next_u64(), base.split(1).next_u64());
+        assert_ne!(base.split(1).next_u64(), base.split(2).next_u64());
+    }
+}
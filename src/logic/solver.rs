@@ -1,14 +1,19 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
 
+use futures_executor::block_on;
 use petgraph::visit::EdgeRef;
 
 use super::model::{self, RelationStrength};
+use super::monitor::Monitor;
+use super::random::Rng;
 
 type Size = u32;
-type Cost = f32;
+pub type Cost = f32;
 type PersonIdx = Size;
 type TableIdx = Size;
 type SeatIdx = Size;
+type GroupIdx = Size;
 
 // Perform a static assertion to ensure Size can be safely cast to usize
 const _: [(); 1 - ((Size::MAX as usize as Size == Size::MAX) as usize)] = [(); 0];
@@ -19,34 +24,381 @@ pub type SolverResult<T> = Result<T, SolverError>;
 pub enum SolverError {
     #[error("the problem is too large, {0} (max {max})", max=Size::MAX)]
     ProblemTooLarge(String),
-    #[error("no solution could be found, {0}")]
-    NoSolution(String),
+    /// Every constraint that cannot be met, at least one.
+    #[error("no solution could be found, {}", join_reasons(.0))]
+    NoSolution(Vec<Infeasibility>),
+    /// The solver found no seats for these persons, although the problem may have a solution.
+    #[error("no seats were found for {}", .0.join(", "))]
+    NotSeated(Vec<model::PersonName>),
     #[error("unknown error")]
     Unknown,
 }
 
+/// A constraint of the problem that cannot be met.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Infeasibility {
+    #[error("there are {persons} persons for {seats} seats")]
+    NotEnoughSeats { persons: usize, seats: usize },
+    #[error("table {table} has {seats} seats, fewer than the minimum of {min_persons} persons")]
+    TableBelowMinimum {
+        table: model::TableName,
+        seats: u32,
+        min_persons: u32,
+    },
+    #[error("{persons} persons cannot reach the minimum of {min_persons} at {tables} tables")]
+    NotEnoughPersons {
+        persons: usize,
+        tables: usize,
+        min_persons: u32,
+    },
+    #[error(
+        "group {group} of {members} must stay together but the largest table has {largest_table} seats"
+    )]
+    GroupTooLarge {
+        group: model::GroupName,
+        members: usize,
+        largest_table: u32,
+    },
+    #[error("group {group} must stay together but is pinned to tables {}", tables.join(", "))]
+    GroupPinnedApart {
+        group: model::GroupName,
+        tables: Vec<model::TableName>,
+    },
+    #[error("table {table} has {seats} seats for {} pinned persons", pinned.len())]
+    TableOverfilledByPins {
+        table: model::TableName,
+        seats: u32,
+        pinned: Vec<model::PersonName>,
+    },
+}
+
+fn join_reasons(reasons: &[Infeasibility]) -> String {
+    reasons
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Fail with all the reasons found, if any.
+fn check_reasons(reasons: Vec<Infeasibility>) -> SolverResult<()> {
+    if reasons.is_empty() {
+        Ok(())
+    } else {
+        Err(SolverError::NoSolution(reasons))
+    }
+}
+
 type RelationStrengthValues = [Cost; RelationStrength::len()];
 
+/// What the solver minimizes.
+///
+/// Whatever the objective, including the default one, the greedy seating is followed by a
+/// local search that moves and swaps persons while the objective decreases.
+#[derive(Clone, Copy, Debug, Default, PartialEq, strum::Display)]
+pub enum Objective {
+    /// The sum of all costs.
+    #[default]
+    Total,
+    /// The cost of the worst-off person, then the sum of all costs.
+    Fairness,
+    /// The sum of all costs plus the cost of the worst-off person scaled by the given weight.
+    Blend(Cost),
+}
+
 #[derive(Clone, Debug)]
 pub struct SolverSettings {
+    pub objective: Objective,
     pub relation_values: RelationStrengthValues,
+    /// Cost of seating two members of a [`model::GroupCohesion::Prefer`] group apart.
+    pub group_split_value: Cost,
+    pub attribute_goals: Vec<model::AttributeGoal>,
+    /// Cost, per table, of every person missing to reach an attribute goal.
+    pub attribute_goal_value: Cost,
+    /// Minimum number of persons at every table that is not closed.
+    pub min_table_persons: u32,
+    /// Leave empty the tables that are not needed to seat everyone, largest tables are kept.
+    pub close_unused_tables: bool,
+    /// Cost, per table, of every person away from a fill proportional to the table size.
+    pub fill_balance_value: Cost,
+    /// Number of successive seatings made by [`solve_rounds`].
+    pub rounds: u32,
+    /// Cost, for every previous round, of seating together two persons that already were.
+    pub repeat_pair_value: Cost,
+    /// Cost of seating a person at another table than in the previous assignment given to
+    /// [`resolve`].
+    pub move_value: Cost,
+    /// Persons that must be seated at a given table, in every round.
+    pub pins: model::Pins,
+    /// Placements kept from a previous solution, same as pins but overridden by them.
+    pub locks: model::Pins,
+    /// Whether to seat persons that have not confirmed yet.
+    pub include_tentative: bool,
+    /// Time to keep searching for better assignments, shared by all rounds.
+    ///
+    /// Without it, the solver stops as soon as no single move or swap improves the assignment.
+    pub time_budget: Option<Duration>,
+    /// Number of candidate assignments to evaluate, shared by all rounds, same as
+    /// [`SolverSettings::time_budget`] but reproducible.
+    ///
+    /// Within a round, every start may use an equal share, whatever threads run them, and the
+    /// rest goes to searching from the best one.
+    pub iteration_budget: Option<u64>,
+    /// Seed of all random choices, the same seed and settings give the same assignments unless
+    /// stopped by the time budget.
+    pub seed: u64,
+    /// Number of assignments built from scratch and improved in every round, keeping the best.
+    ///
+    /// Persons are seated in the order of the tribe first, then the most constrained first,
+    /// then in random orders.
+    pub starts: u32,
+    /// Number of distinct assignments to offer for every round, the best ones found.
+    ///
+    /// At least as many starts are run, and more are found with a search budget.
+    pub alternatives: u32,
+    /// Minimum number of persons seated at another table between any two alternatives.
+    pub alternative_min_moved: u32,
+    /// Number of threads running starts at once, all cores if zero.
+    ///
+    /// Only used with the `parallel` feature, on native targets.
+    pub threads: u32,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        Self {
+            objective: Objective::default(),
+            relation_values: [4.0, 1.0, -1.0, -4.0],
+            group_split_value: 2.0,
+            attribute_goals: Vec::new(),
+            attribute_goal_value: 3.0,
+            min_table_persons: 0,
+            close_unused_tables: false,
+            fill_balance_value: 0.0,
+            rounds: 1,
+            repeat_pair_value: 2.0,
+            move_value: 5.0,
+            pins: model::Pins::new(),
+            locks: model::Pins::new(),
+            include_tentative: true,
+            time_budget: None,
+            iteration_budget: None,
+            seed: 0,
+            starts: 1,
+            alternatives: 1,
+            alternative_min_moved: 3,
+            threads: 0,
+        }
+    }
 }
 
-pub fn solve(tables: &model::Tables, tribe: &model::Tribe) -> SolverResult<model::Assignment> {
-    let mut solver = Solver::new(
+impl SolverSettings {
+    /// The table of every pinned or locked person.
+    pub fn fixed_tables(&self) -> model::Pins {
+        let mut fixed = self.locks.clone();
+        fixed.extend(self.pins.clone());
+        fixed
+    }
+}
+
+/// Seat everyone, searching for as long as the budgets of the settings allow.
+///
+/// See [`resolve_rounds_monitored`] to also know how the solve went.
+pub fn solve(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: SolverSettings,
+) -> SolverResult<model::Assignment> {
+    let tribe = tribe.attending(settings.include_tentative);
+    let mut solver = Solver::new(tables, &tribe, settings)?;
+    solver.solve()
+}
+
+/// Solve again, moving as few persons as possible from a previous assignment.
+pub fn resolve(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: SolverSettings,
+    previous: &model::Assignment,
+) -> SolverResult<model::Assignment> {
+    let tribe = tribe.attending(settings.include_tentative);
+    let mut solver = Solver::new(tables, &tribe, settings)?;
+    solver.set_previous(previous);
+    solver.solve()
+}
+
+/// Solve successive rounds, avoiding to seat together persons that already were.
+pub fn solve_rounds(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: SolverSettings,
+) -> SolverResult<Vec<model::Assignment>> {
+    resolve_rounds(tables, tribe, settings, &[])
+}
+
+/// Solve successive rounds, moving as few persons as possible from previous rounds.
+///
+/// Rounds without a previous assignment are solved from scratch.
+pub fn resolve_rounds(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: SolverSettings,
+    previous_rounds: &[model::Assignment],
+) -> SolverResult<Vec<model::Assignment>> {
+    block_on(resolve_rounds_monitored(
         tables,
         tribe,
-        SolverSettings {
-            relation_values: [-4.0, -1.0, 1.0, 4.0],
+        settings,
+        previous_rounds,
+        &mut Monitor::new(),
+    ))
+    .map(|output| output.rounds)
+}
+
+/// How a solve went.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolveStats {
+    /// Number of candidate assignments evaluated.
+    pub iterations: u64,
+    pub elapsed: Duration,
+    /// Seed of the random choices, to solve again the same way.
+    pub seed: u64,
+}
+
+/// One of the best assignments found for a round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alternative {
+    pub assignment: model::Assignment,
+    /// Value of the [`Objective`], lower is better.
+    pub cost: Cost,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolverOutput {
+    pub rounds: Vec<model::Assignment>,
+    pub scores: Vec<Score>,
+    /// Best assignments of every round, best first, the first one being the round itself.
+    pub alternatives: Vec<Vec<Alternative>>,
+    pub stats: SolveStats,
+}
+
+/// Same as [`resolve_rounds`], reporting progress and stopping early when cancelled.
+///
+/// Once cancelled, the rounds solved so far are returned, the last one being the best
+/// assignment found before stopping.
+/// With a [`SolverSettings::time_budget`] or [`SolverSettings::iteration_budget`], every round
+/// keeps searching until its share of the budget is spent.
+/// In browsers, the solve pauses now and then to let the page handle events.
+pub async fn resolve_rounds_monitored(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: SolverSettings,
+    previous_rounds: &[model::Assignment],
+    monitor: &mut Monitor<'_>,
+) -> SolverResult<SolverOutput> {
+    let tribe = tribe.attending(settings.include_tentative);
+    let n_rounds = settings.rounds as usize;
+    let mut rounds = Vec::with_capacity(n_rounds);
+    let mut alternatives = Vec::with_capacity(n_rounds);
+    let mut scores = Vec::with_capacity(n_rounds);
+    for r in 0..n_rounds {
+        let mut solver = Solver::new(tables, &tribe, settings.clone())?;
+        for met in rounds.iter() {
+            solver.add_met_pairs(met);
+        }
+        if let Some(previous) = previous_rounds.get(r) {
+            solver.set_previous(previous);
+        }
+        monitor.start_round(r);
+        monitor.set_deadline(
+            settings
+                .time_budget
+                .map(|budget| budget.mul_f64((r + 1) as f64 / n_rounds as f64)),
+        );
+        monitor.set_iteration_limit(
+            settings
+                .iteration_budget
+                .map(|budget| budget * (r + 1) as u64 / n_rounds as u64),
+        );
+        rounds.push(solver.solve_monitored(monitor).await?);
+        alternatives.push(solver.alternatives());
+        monitor.pause().await;
+        scores.push(solver.score());
+        if monitor.is_cancelled() {
+            break;
+        }
+    }
+    Ok(SolverOutput {
+        rounds,
+        scores,
+        alternatives,
+        stats: SolveStats {
+            iterations: monitor.iteration(),
+            elapsed: monitor.elapsed(),
+            seed: settings.seed,
         },
-    )?;
-    solver.solve()
+    })
+}
+
+/// How good an assignment is, lower costs are better.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub total_cost: Cost,
+    /// The person with the highest cost, with that cost.
+    pub worst_person: Option<(model::PersonName, Cost)>,
+    /// A total cost that no assignment of the problem can go below.
+    pub lower_bound: Cost,
+}
+
+impl Score {
+    /// How much lower the total cost could be at most.
+    ///
+    /// The bound is loose on problems with many relations, so a large gap does not mean a
+    /// much better assignment exists.
+    pub fn gap(&self) -> Cost {
+        (self.total_cost - self.lower_bound).max(0.0)
+    }
+}
+
+/// Tables with seats that the solver leaves empty, as they are not needed to seat everyone.
+///
+/// Empty when tables are not closed, or when the problem has no solution.
+pub fn closed_tables(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: &SolverSettings,
+) -> BTreeSet<model::TableName> {
+    let tribe = tribe.attending(settings.include_tentative);
+    match Solver::build_tables(tables, &tribe, settings) {
+        // Tables are all left open when the problem has no solution
+        Ok((names, sizes, _)) => names
+            .into_iter()
+            .zip(sizes)
+            .filter(|(name, size)| *size == 0 && tables[*name].n_seats > 0)
+            .map(|(name, _)| name.to_owned())
+            .collect(),
+        Err(_) => BTreeSet::new(),
+    }
+}
+
+/// Evaluate any assignment, ignoring persons and tables that are not part of the problem.
+pub fn score(
+    tables: &model::Tables,
+    tribe: &model::Tribe,
+    settings: SolverSettings,
+    assignment: &model::Assignment,
+) -> SolverResult<Score> {
+    let tribe = tribe.attending(settings.include_tentative);
+    let mut solver = Solver::new(tables, &tribe, settings)?;
+    solver.seat(assignment);
+    Ok(solver.score())
 }
 
 #[derive(Clone, Debug)]
 struct BackwardMapping<'a> {
     table_names: Vec</* TableIdx, */ &'a model::TableNameRef>,
     person_names: Vec</* PersonIdx, */ &'a model::PersonNameRef>,
+    group_names: Vec</* GroupIdx, */ &'a model::GroupNameRef>,
 }
 
 impl<'a> BackwardMapping<'a> {
@@ -57,9 +409,18 @@ impl<'a> BackwardMapping<'a> {
         Self {
             table_names,
             person_names,
+            group_names: Vec::new(),
         }
     }
 
+    pub fn with_group_names(
+        mut self,
+        group_names: Vec</* GroupIdx, */ &'a model::GroupNameRef>,
+    ) -> Self {
+        self.group_names = group_names;
+        self
+    }
+
     pub fn table_name(&self, idx: TableIdx) -> Option<&'a model::TableNameRef> {
         self.table_names.get(idx as usize).copied()
     }
@@ -67,6 +428,25 @@ impl<'a> BackwardMapping<'a> {
     pub fn person_name(&self, idx: PersonIdx) -> Option<&'a model::PersonNameRef> {
         self.person_names.get(idx as usize).copied()
     }
+
+    pub fn group_name(&self, idx: GroupIdx) -> Option<&'a model::GroupNameRef> {
+        self.group_names.get(idx as usize).copied()
+    }
+
+    pub fn table_idx(&self, name: &model::TableNameRef) -> Option<TableIdx> {
+        self.table_names
+            .iter()
+            .position(|t| *t == name)
+            .map(|idx| idx as TableIdx)
+    }
+
+    pub fn person_idx(&self, name: &model::PersonNameRef) -> Option<PersonIdx> {
+        // Persons are sorted as they come from the tribe
+        self.person_names
+            .binary_search(&name)
+            .ok()
+            .map(|idx| idx as PersonIdx)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -155,6 +535,10 @@ impl Assignor {
         self.table_slice(table).len() as Size
     }
 
+    pub fn table_free_seat_count(&self, table: TableIdx) -> Size {
+        self.table_seat_count(table) - self.table_person_count(table)
+    }
+
     pub fn table_is_full(&self, table: TableIdx) -> bool {
         self.table_slice(table)
             .last()
@@ -175,8 +559,8 @@ impl Assignor {
         self.person_assignment[person as usize] != Self::UNASSIGNED_PERSON
     }
 
-    pub fn can_assign(&mut self, person: PersonIdx, table: TableIdx) -> bool {
-        !self.person_is_seated(person) && !self.table_is_full(table)
+    pub fn person_table(&self, person: PersonIdx) -> Option<TableIdx> {
+        Some(self.person_assignment[person as usize]).filter(|t| *t != Self::UNASSIGNED_PERSON)
     }
 
     pub fn assign(&mut self, person: PersonIdx, table: TableIdx) -> bool {
@@ -194,16 +578,83 @@ impl Assignor {
             false
         }
     }
+
+    pub fn unassign(&mut self, person: PersonIdx) -> bool {
+        let Some(table) = self.person_table(person) else {
+            return false;
+        };
+
+        // Persons are packed at the start of the table so we swap with the last one
+        let count = self.table_person_count(table) as usize;
+        let seats = self.table_slice_mut(table);
+        let offset = seats[..count].iter().position(|p| *p == person).unwrap();
+        seats.swap(offset, count - 1);
+        seats[count - 1] = Self::UNASSIGNED_SEAT;
+        self.person_assignment[person as usize] = Self::UNASSIGNED_PERSON;
+        true
+    }
 }
 
 type RelationGraph = petgraph::csr::Csr<(), RelationStrength, petgraph::Undirected, PersonIdx>;
 
+#[derive(Clone, Debug)]
+struct Group {
+    members: Vec<PersonIdx>,
+    cohesion: model::GroupCohesion,
+}
+
+#[derive(Clone, Debug)]
+enum Goal {
+    AtLeast {
+        holders: Vec</* PersonIdx, */ bool>,
+        count: Size,
+    },
+    Mix {
+        values: Vec</* PersonIdx, */ Vec<Size>>,
+        value_count: Size,
+    },
+}
+
+impl Goal {
+    /// How many persons are missing for a table seating the given persons to meet the goal.
+    pub fn shortfall(&self, persons: impl Iterator<Item = PersonIdx>) -> Size {
+        match self {
+            Goal::AtLeast { holders, count } => {
+                let holder_count = persons.filter(|p| holders[*p as usize]).count() as Size;
+                count.saturating_sub(holder_count)
+            }
+            Goal::Mix {
+                values,
+                value_count,
+            } => {
+                let present = persons
+                    .flat_map(|p| values[p as usize].iter())
+                    .collect::<BTreeSet<_>>();
+                value_count - present.len() as Size
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct AssignorWithCosts {
     assignor: Assignor,
     relations: RelationGraph,
     relations_values: RelationStrengthValues,
+    groups: Vec</* GroupIdx, */ Group>,
+    person_groups: Vec</* PersonIdx, */ Option<GroupIdx>>,
+    group_split_value: Cost,
+    goals: Vec<Goal>,
+    goal_value: Cost,
+    fill_targets: Vec</* TableIdx, */ Cost>,
+    fill_balance_value: Cost,
+    met_pairs: HashMap<(PersonIdx, PersonIdx), Size>,
+    repeat_pair_value: Cost,
+    previous_tables: Vec</* PersonIdx, */ Option<TableIdx>>,
+    move_value: Cost,
     table_costs: Vec</* TableIdx */ Cost>,
+    table_worsts: Vec</* TableIdx, */ Option<(PersonIdx, Cost)>>,
+    stale_worsts: Vec</* TableIdx, */ bool>,
 }
 
 impl AssignorWithCosts {
@@ -213,14 +664,125 @@ impl AssignorWithCosts {
         relations_values: RelationStrengthValues,
     ) -> Self {
         let n_tables = assignor.table_count();
+        let n_persons = assignor.person_count();
         Self {
             assignor,
             relations,
             relations_values,
+            groups: Vec::new(),
+            person_groups: vec![None; n_persons as usize],
+            group_split_value: 0.0,
+            goals: Vec::new(),
+            goal_value: 0.0,
+            fill_targets: vec![0.0; n_tables as usize],
+            fill_balance_value: 0.0,
+            met_pairs: HashMap::new(),
+            repeat_pair_value: 0.0,
+            previous_tables: vec![None; n_persons as usize],
+            move_value: 0.0,
             table_costs: vec![0.0; n_tables as usize],
+            table_worsts: vec![None; n_tables as usize],
+            stale_worsts: vec![true; n_tables as usize],
+        }
+    }
+
+    pub fn with_goals(mut self, goals: Vec<Goal>, goal_value: Cost) -> Self {
+        self.goals = goals;
+        self.goal_value = goal_value;
+        self
+    }
+
+    /// Penalize tables with a person count away from a fill proportional to their size.
+    pub fn with_fill_balance(mut self, fill_balance_value: Cost) -> Self {
+        let n_persons = self.assignor.person_count() as Cost;
+        let n_seats = self.assignor.seat_count() as Cost;
+        for t in self.assignor.tables() {
+            let n_table_seats = self.assignor.table_seat_count(t) as Cost;
+            self.fill_targets[t as usize] = if n_seats > 0.0 {
+                n_persons * n_table_seats / n_seats
+            } else {
+                0.0
+            };
+        }
+        self.fill_balance_value = fill_balance_value;
+        self
+    }
+
+    pub fn with_groups(mut self, groups: Vec<Group>, group_split_value: Cost) -> Self {
+        self.person_groups.fill(None);
+        for (g, group) in groups.iter().enumerate() {
+            for p in group.members.iter() {
+                self.person_groups[*p as usize] = Some(g as GroupIdx);
+            }
+        }
+        self.groups = groups;
+        self.group_split_value = group_split_value;
+        self
+    }
+
+    pub fn with_repeat_pair_value(mut self, repeat_pair_value: Cost) -> Self {
+        self.repeat_pair_value = repeat_pair_value;
+        self
+    }
+
+    /// Record that two persons have been seated together, before any assignment.
+    pub fn add_met_pair(&mut self, person1: PersonIdx, person2: PersonIdx) {
+        let key = (person1.min(person2), person1.max(person2));
+        *self.met_pairs.entry(key).or_default() += 1;
+    }
+
+    fn met_count(&self, person1: PersonIdx, person2: PersonIdx) -> Size {
+        let key = (person1.min(person2), person1.max(person2));
+        self.met_pairs.get(&key).copied().unwrap_or(0)
+    }
+
+    /// The cost of seating a person with persons they already met.
+    fn repeat_cost(&self, person: PersonIdx, table_persons: &[PersonIdx]) -> Cost {
+        if self.met_pairs.is_empty() {
+            return 0.0;
+        }
+        let repeats: Size = table_persons
+            .iter()
+            .filter(|p| **p != person)
+            .map(|p| self.met_count(person, *p))
+            .sum();
+        repeats as Cost * self.repeat_pair_value
+    }
+
+    pub fn with_move_value(mut self, move_value: Cost) -> Self {
+        self.move_value = move_value;
+        self
+    }
+
+    /// Record the table of a person in a previous assignment, before any assignment.
+    pub fn set_previous_table(&mut self, person: PersonIdx, table: TableIdx) {
+        self.previous_tables[person as usize] = Some(table);
+    }
+
+    /// The table of a person in a previous assignment.
+    pub fn previous_table(&self, person: PersonIdx) -> Option<TableIdx> {
+        self.previous_tables[person as usize]
+    }
+
+    /// The cost of seating a person away from their previous table.
+    fn move_cost(&self, person: PersonIdx, table: TableIdx) -> Cost {
+        match self.previous_tables[person as usize] {
+            Some(previous) if previous != table => self.move_value,
+            _ => 0.0,
         }
     }
 
+    pub fn groups(&self) -> impl Iterator<Item = (GroupIdx, &Group)> {
+        self.groups
+            .iter()
+            .enumerate()
+            .map(|(g, group)| (g as GroupIdx, group))
+    }
+
+    pub fn person_group(&self, person: PersonIdx) -> Option<&Group> {
+        self.person_groups[person as usize].map(|g| &self.groups[g as usize])
+    }
+
     pub fn table_cost(&self, table: TableIdx) -> Cost {
         self.table_costs[table as usize]
     }
@@ -228,19 +790,225 @@ impl AssignorWithCosts {
     pub fn assign_with_cost(&mut self, person: PersonIdx, table: TableIdx, cost: Cost) -> bool {
         if self.assignor.assign(person, table) {
             self.table_costs[table as usize] += cost;
+            self.mark_stale_worsts(person, table);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Unassign a person, removing the cost they add to their table.
+    pub fn unassign_with_cost(&mut self, person: PersonIdx) -> bool {
+        let Some(table) = self.assignor.person_table(person) else {
+            return false;
+        };
+        self.assignor.unassign(person);
+        self.table_costs[table as usize] -= self.assignment_cost(person, table);
+        self.mark_stale_worsts(person, table);
+        true
+    }
+
+    /// Mark the tables whose worst-off person may change when a person joins or leaves a table.
+    fn mark_stale_worsts(&mut self, person: PersonIdx, table: TableIdx) {
+        self.stale_worsts[table as usize] = true;
+        let Some(g) = self.person_groups[person as usize] else {
+            return;
+        };
+        let group = &self.groups[g as usize];
+        if group.cohesion == model::GroupCohesion::Prefer {
+            // The split cost of every other member depends on the table of the person
+            for t in group
+                .members
+                .iter()
+                .filter_map(|m| self.assignor.person_table(*m))
+            {
+                self.stale_worsts[t as usize] = true;
+            }
+        }
+    }
+
+    /// The seated person with the highest cost, only evaluating tables changed since last time.
+    pub fn worst_person(&mut self) -> Option<(PersonIdx, Cost)> {
+        for t in self.assignor.tables() {
+            if self.stale_worsts[t as usize] {
+                let worst = self
+                    .assignor
+                    .table_persons(t)
+                    .iter()
+                    .map(|p| (*p, self.person_cost(*p)))
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                self.table_worsts[t as usize] = worst;
+                self.stale_worsts[t as usize] = false;
+            }
+        }
+        self.table_worsts
+            .iter()
+            .flatten()
+            .copied()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+    }
+
+    /// Move a seated person to another table, updating costs.
+    pub fn reassign_with_cost(&mut self, person: PersonIdx, table: TableIdx) -> bool {
+        let Some(prev) = self.assignor.person_table(person) else {
+            return false;
+        };
+        self.unassign_with_cost(person);
+        let cost = self.assignment_cost(person, table);
+        if self.assign_with_cost(person, table, cost) {
             true
         } else {
+            let cost = self.assignment_cost(person, prev);
+            self.assign_with_cost(person, prev, cost);
             false
         }
     }
 
+    /// Exchange the tables of two seated persons, updating costs.
+    pub fn swap_with_cost(&mut self, person1: PersonIdx, person2: PersonIdx) -> bool {
+        let tables = self
+            .assignor
+            .person_table(person1)
+            .zip(self.assignor.person_table(person2));
+        let Some((table1, table2)) = tables else {
+            return false;
+        };
+        self.unassign_with_cost(person1);
+        self.unassign_with_cost(person2);
+        let cost = self.assignment_cost(person1, table2);
+        self.assign_with_cost(person1, table2, cost);
+        let cost = self.assignment_cost(person2, table1);
+        self.assign_with_cost(person2, table1, cost);
+        true
+    }
+
+    /// A total cost that no assignment can go below, ignoring who is already seated.
+    ///
+    /// Every person is counted as seated with the persons they get along with best, as many as
+    /// fit at the largest table, with every table meeting its fill target and attribute goals,
+    /// and no other cost.
+    pub fn lower_bound(&self) -> Cost {
+        let n_neighbors = self
+            .assignor
+            .tables()
+            .map(|t| self.assignor.table_seat_count(t))
+            .max()
+            .unwrap_or(0)
+            .saturating_sub(1) as usize;
+
+        // Both persons of a pair count its cost, so every pair is counted twice
+        let mut pair_costs = BTreeMap::<PersonIdx, Cost>::new();
+        let mut twice_relations = 0.0;
+        for person in self.assignor.persons() {
+            pair_costs.clear();
+            for edge in self
+                .relations
+                .edges(person)
+                .filter(|e| e.target() != person)
+            {
+                *pair_costs.entry(edge.target()).or_default() +=
+                    self.relations_values[*edge.weight() as usize];
+            }
+            let mut gains = pair_costs
+                .values()
+                .copied()
+                .filter(|c| *c < 0.0)
+                .collect::<Vec<_>>();
+            gains.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            twice_relations += gains.iter().take(n_neighbors).sum::<Cost>();
+        }
+
+        // Costs of fill and goals are counted from empty tables, where they are the highest
+        let fill = -self.fill_balance_value * self.fill_targets.iter().sum::<Cost>();
+        let goals: Size = self
+            .assignor
+            .tables()
+            .filter(|t| self.assignor.table_seat_count(*t) > 0)
+            .flat_map(|_| self.goals.iter().map(|g| g.shortfall(std::iter::empty())))
+            .sum();
+
+        twice_relations / 2.0 + fill - goals as Cost * self.goal_value
+    }
+
+    /// The sum of the costs of all tables.
+    pub fn total_cost(&self) -> Cost {
+        self.assignor.tables().map(|t| self.table_cost(t)).sum()
+    }
+
+    /// The cost of a seated person given everyone else's seat.
+    ///
+    /// Attribute goals concern whole tables and are not attributed to any person.
+    pub fn person_cost(&self, person: PersonIdx) -> Cost {
+        let Some(table) = self.assignor.person_table(person) else {
+            return 0.0;
+        };
+        let table_persons = self.assignor.table_persons(table);
+        let relations_cost: Cost = self
+            .relations
+            .edges(person)
+            .filter(|e| e.target() != person && table_persons.contains(&e.target()))
+            .map(|e| self.relations_values[*e.weight() as usize])
+            .sum();
+        relations_cost
+            + self.group_split_cost(person, table)
+            + self.repeat_cost(person, table_persons)
+            + self.move_cost(person, table)
+    }
+
     pub fn assignment_cost(&self, person: PersonIdx, table: TableIdx) -> Cost {
         let table_persons = self.assignor.table_persons(table);
-        self.relations
+        let relations_cost: Cost = self
+            .relations
             .edges(person)
             .filter(|e| table_persons.contains(&e.target()))
             .map(|e| self.relations_values[*e.weight() as usize])
-            .sum()
+            .sum();
+        relations_cost
+            + self.group_split_cost(person, table)
+            + self.repeat_cost(person, table_persons)
+            + self.move_cost(person, table)
+            + self.goal_cost(person, table)
+            + self.fill_cost(table)
+    }
+
+    /// The change in distance to the table fill target when adding a person.
+    fn fill_cost(&self, table: TableIdx) -> Cost {
+        let target = self.fill_targets[table as usize];
+        let count = self.assignor.table_person_count(table) as Cost;
+        let change = (count + 1.0 - target).abs() - (count - target).abs();
+        change * self.fill_balance_value
+    }
+
+    /// The change in attribute goals shortfall of the table when adding the person.
+    fn goal_cost(&self, person: PersonIdx, table: TableIdx) -> Cost {
+        let table_persons = self.assignor.table_persons(table);
+        let shortfall_change: i64 = self
+            .goals
+            .iter()
+            .map(|g| {
+                let before = g.shortfall(table_persons.iter().copied());
+                let after = g.shortfall(table_persons.iter().copied().chain([person]));
+                after as i64 - before as i64
+            })
+            .sum();
+        shortfall_change as Cost * self.goal_value
+    }
+
+    /// The cost of group members already seated at another table than the given one.
+    fn group_split_cost(&self, person: PersonIdx, table: TableIdx) -> Cost {
+        match self.person_group(person) {
+            Some(group) if group.cohesion == model::GroupCohesion::Prefer => {
+                let split_count = group
+                    .members
+                    .iter()
+                    .filter(|m| **m != person)
+                    .filter_map(|m| self.assignor.person_table(*m))
+                    .filter(|t| *t != table)
+                    .count();
+                split_count as Cost * self.group_split_value
+            }
+            _ => 0.0,
+        }
     }
 }
 
@@ -264,31 +1032,229 @@ impl AsRef<RelationGraph> for AssignorWithCosts {
     }
 }
 
+/// Value of the [`Objective`], compared lexicographically.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ObjectiveValue {
+    primary: Cost,
+    secondary: Cost,
+}
+
+impl ObjectiveValue {
+    /// Tolerance on costs to avoid cycling on rounding errors.
+    const EPSILON: Cost = 1e-4;
+
+    pub fn improves_on(&self, other: &Self) -> bool {
+        if (self.primary - other.primary).abs() > Self::EPSILON {
+            self.primary < other.primary
+        } else {
+            self.secondary < other.secondary - Self::EPSILON
+        }
+    }
+
+    /// Order values exactly, lower first, to choose between assignments the same way wherever
+    /// they are compared.
+    ///
+    /// Unlike [`ObjectiveValue::improves_on`], differences below the tolerance count.
+    pub fn rank(&self, other: &Self) -> std::cmp::Ordering {
+        self.primary
+            .total_cmp(&other.primary)
+            .then(self.secondary.total_cmp(&other.secondary))
+    }
+}
+
+/// An improved assignment, kept to offer alternatives.
+#[derive(Clone, Debug)]
+struct Candidate {
+    value: ObjectiveValue,
+    person_tables: Vec</* PersonIdx, */ Option<TableIdx>>,
+}
+
+/// The best candidates found that differ from each other by enough moved persons.
+#[derive(Clone, Debug)]
+struct Alternatives {
+    capacity: usize,
+    min_moved: usize,
+    /// Best first.
+    kept: Vec<Candidate>,
+}
+
+impl Alternatives {
+    fn new(capacity: usize, min_moved: usize) -> Self {
+        Self {
+            capacity,
+            min_moved,
+            kept: Vec::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Keep a candidate if it is better than the kept ones close to it, which it replaces.
+    fn add(&mut self, candidate: Candidate) {
+        let is_close = |kept: &Candidate| {
+            let moved = kept
+                .person_tables
+                .iter()
+                .zip(candidate.person_tables.iter())
+                .filter(|(t1, t2)| t1 != t2)
+                .count();
+            moved < self.min_moved
+        };
+        if self
+            .kept
+            .iter()
+            .any(|k| is_close(k) && !candidate.value.rank(&k.value).is_lt())
+        {
+            return;
+        }
+        self.kept.retain(|k| !is_close(k));
+        let position = self
+            .kept
+            .iter()
+            .position(|k| candidate.value.rank(&k.value).is_lt())
+            .unwrap_or(self.kept.len());
+        self.kept.insert(position, candidate);
+        self.kept.truncate(self.capacity);
+    }
+}
+
 #[derive(Clone, Debug)]
 struct Solver<'a> {
     assignor: AssignorWithCosts,
     mapping: BackwardMapping<'a>,
+    pinned_tables: Vec</* PersonIdx, */ Option<TableIdx>>,
+    /// Constraints on tables found not to be met while opening them.
+    table_reasons: Vec<Infeasibility>,
+    rng: Rng,
+    alternatives: Alternatives,
     settings: SolverSettings,
 }
 
+/// In which order persons are seated when building an assignment from scratch.
+#[derive(Clone, Debug)]
+enum SeatingOrder {
+    /// The order of persons in the tribe.
+    Tribe,
+    /// Persons with the most relations first.
+    MostConstrained,
+    Random(Rng),
+    /// Persons at their table of a previous assignment first, the others in the order of the
+    /// tribe.
+    Previous,
+}
+
+/// The best assignment of a multi-start solve, or the first error if none could be built.
+#[derive(Default)]
+struct BestStart {
+    best: Option<(u32, AssignorWithCosts, ObjectiveValue)>,
+    error: Option<(u32, SolverError)>,
+    /// The assignment built by every start.
+    candidates: Vec<(u32, Candidate)>,
+}
+
+impl BestStart {
+    fn add(&mut self, start: u32, result: SolverResult<(AssignorWithCosts, ObjectiveValue)>) {
+        match result {
+            Ok((assignor, value)) => {
+                // Ties go to the earliest start, whatever order starts were run in
+                let better = self
+                    .best
+                    .as_ref()
+                    .is_none_or(|(s, _, v)| value.rank(v).then(start.cmp(s)).is_lt());
+                if better {
+                    self.best = Some((start, assignor, value));
+                }
+            }
+            Err(err) => {
+                if self.error.as_ref().is_none_or(|(s, _)| start < *s) {
+                    self.error = Some((start, err));
+                }
+            }
+        }
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    fn merge(&mut self, other: Self) {
+        self.candidates.extend(other.candidates);
+        if let Some((start, assignor, value)) = other.best {
+            self.add(start, Ok((assignor, value)));
+        }
+        if let Some((start, err)) = other.error {
+            self.add(start, Err(err));
+        }
+    }
+
+    /// The candidates from best to worst, in the same order however starts were run.
+    fn sorted_candidates(&mut self) -> Vec<Candidate> {
+        let mut candidates = std::mem::take(&mut self.candidates);
+        candidates.sort_by(|(s1, c1), (s2, c2)| c1.value.rank(&c2.value).then(s1.cmp(s2)));
+        candidates.into_iter().map(|(_, c)| c).collect()
+    }
+
+    fn into_result(self) -> SolverResult<AssignorWithCosts> {
+        match (self.best, self.error) {
+            (Some((_, assignor, _)), _) => Ok(assignor),
+            (None, Some((_, err))) => Err(err),
+            // At least one start is always run
+            (None, None) => Err(SolverError::Unknown),
+        }
+    }
+}
+
+/// Persons seated together at once, possibly at a required table.
+#[derive(Clone, Debug)]
+struct SeatingBlock {
+    persons: Vec<PersonIdx>,
+    table: Option<TableIdx>,
+}
+
 impl<'pb> Solver<'pb> {
     pub fn new(
         tables: &'pb model::Tables,
         tribe: &'pb model::Tribe,
         settings: SolverSettings,
     ) -> SolverResult<Self> {
-        let (table_names, table_sizes) = Self::build_tables(tables)?;
+        let (table_names, table_sizes, table_reasons) =
+            Self::build_tables(tables, tribe, &settings)?;
         let (relations, persons) = Self::build_relations(tribe)?;
+        let (groups, group_names) = Self::build_groups(tribe, &persons);
+        let goals = Self::build_goals(tribe, &persons, &settings.attribute_goals);
+        let mapping = BackwardMapping::new(table_names, persons).with_group_names(group_names);
+        let pinned_tables = Self::build_pins(&mapping, &settings.fixed_tables());
 
         let assignor = Assignor::from_table_sizes(table_sizes, tribe.persons_count() as Size);
 
         Ok(Self {
-            assignor: AssignorWithCosts::new(assignor, relations, settings.relation_values),
-            mapping: BackwardMapping::new(table_names, persons),
+            assignor: AssignorWithCosts::new(assignor, relations, settings.relation_values)
+                .with_groups(groups, settings.group_split_value)
+                .with_goals(goals, settings.attribute_goal_value)
+                .with_fill_balance(settings.fill_balance_value)
+                .with_repeat_pair_value(settings.repeat_pair_value)
+                .with_move_value(settings.move_value),
+            mapping,
+            pinned_tables,
+            table_reasons,
+            rng: Rng::new(settings.seed),
+            alternatives: Alternatives::new(
+                settings.alternatives.max(1) as usize,
+                settings.alternative_min_moved as usize,
+            ),
             settings,
         })
     }
 
+    /// The table of every pinned person, ignoring pins outside of the problem.
+    fn build_pins(
+        mapping: &BackwardMapping,
+        pins: &model::Pins,
+    ) -> Vec</* PersonIdx, */ Option<TableIdx>> {
+        let mut pinned_tables = vec![None; mapping.person_names.len()];
+        for (person, table) in pins.iter() {
+            if let Some((p, t)) = mapping.person_idx(person).zip(mapping.table_idx(table)) {
+                pinned_tables[p as usize] = Some(t);
+            }
+        }
+        pinned_tables
+    }
+
     pub fn build_relations(
         tribe: &model::Tribe,
     ) -> SolverResult<(RelationGraph, Vec</* PersonIdx, */ &model::PersonNameRef>)> {
@@ -307,7 +1273,7 @@ impl<'pb> Solver<'pb> {
             .collect::<BTreeMap<&model::PersonNameRef, PersonIdx>>();
 
         let mut relations = RelationGraph::with_nodes(tribe.persons_count());
-        for (p1, p2, strenght) in tribe.relations() {
+        for (p1, p2, strenght) in tribe.relations().chain(tribe.group_relations()) {
             relations.add_edge(
                 // Safe because all indices added
                 *persons_forward.get(p1.as_str()).unwrap(),
@@ -320,9 +1286,83 @@ impl<'pb> Solver<'pb> {
         Ok((relations, persons))
     }
 
+    fn build_groups<'a>(
+        tribe: &'a model::Tribe,
+        persons: &[&model::PersonNameRef],
+    ) -> (Vec<Group>, Vec</* GroupIdx, */ &'a model::GroupNameRef>) {
+        let persons_forward = persons
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(idx, name)| (name, idx as PersonIdx))
+            .collect::<BTreeMap<&model::PersonNameRef, PersonIdx>>();
+
+        tribe
+            .groups()
+            .map(|(name, group)| {
+                let members = group
+                    .members()
+                    // Safe because group members are persons of the tribe
+                    .map(|p| *persons_forward.get(p.as_str()).unwrap())
+                    .collect();
+                let group = Group {
+                    members,
+                    cohesion: group.cohesion,
+                };
+                (group, name.as_str())
+            })
+            .unzip()
+    }
+
+    fn build_goals(
+        tribe: &model::Tribe,
+        persons: &[&model::PersonNameRef],
+        goals: &[model::AttributeGoal],
+    ) -> Vec<Goal> {
+        goals
+            .iter()
+            .map(|goal| match goal {
+                model::AttributeGoal::AtLeast { attribute, count } => Goal::AtLeast {
+                    holders: persons
+                        .iter()
+                        .map(|p| tribe.has_attribute(p, attribute))
+                        .collect(),
+                    count: *count,
+                },
+                model::AttributeGoal::Mix { name } => {
+                    let values_forward = tribe
+                        .attribute_values(name)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, value)| (value, idx as Size))
+                        .collect::<BTreeMap<_, _>>();
+                    Goal::Mix {
+                        values: persons
+                            .iter()
+                            .map(|p| {
+                                tribe
+                                    .person_attributes(p)
+                                    .filter(|a| a.name == *name)
+                                    // Safe because all values were collected
+                                    .map(|a| *values_forward.get(&a.value).unwrap())
+                                    .collect()
+                            })
+                            .collect(),
+                        value_count: values_forward.len() as Size,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Table names and sizes, sorted by size, with the constraints on tables that cannot be met.
+    ///
+    /// Tables that are closed get no seats.
     fn build_tables<'a>(
         tables: &'a model::Tables,
-    ) -> SolverResult<(Vec<&'a model::TableNameRef>, Vec<Size>)> {
+        tribe: &model::Tribe,
+        settings: &SolverSettings,
+    ) -> SolverResult<(Vec<&'a model::TableNameRef>, Vec<Size>, Vec<Infeasibility>)> {
         if tables.len() >= (Size::MAX as usize) {
             return Err(SolverError::ProblemTooLarge(
                 "there are too many tables".into(),
@@ -345,43 +1385,740 @@ impl<'pb> Solver<'pb> {
             table_names.sort_unstable_by_key(|n| tables.get(*n).unwrap().n_seats);
             table_sizes.sort();
             assert_eq!(table_names.len(), table_sizes.len());
-            Ok((table_names, table_sizes))
+            let fixed = settings.fixed_tables();
+            let pinned = fixed
+                .iter()
+                .filter(|(p, _)| tribe.contains_person(p))
+                .map(|(_, t)| t.as_str())
+                .collect::<BTreeSet<_>>();
+            let n_persons = tribe.persons_count();
+            let reasons =
+                Self::open_tables(&table_names, &mut table_sizes, &pinned, n_persons, settings);
+            Ok((table_names, table_sizes, reasons))
         }
     }
 
-    fn assignment(&self) -> model::Assignment {
-        let mut out = model::Assignment::new();
-        for (table_idx, persons_idx) in self.assignor.table_assignment() {
-            let table_name = self.mapping.table_name(table_idx).unwrap().to_owned();
-            let person_names: Vec<_> = persons_idx
-                .iter()
-                .map(|p| self.mapping.person_name(*p).unwrap().to_owned())
-                .collect();
-            let prev = out.insert(table_name, person_names);
-            assert!(prev.is_none());
+    /// Close tables by removing their seats, returning why minimum occupancy cannot be met, if
+    /// so. Tables are left open then.
+    ///
+    /// Tables with pinned persons are never closed.
+    fn open_tables(
+        table_names: &[&model::TableNameRef],
+        table_sizes: &mut [Size],
+        pinned: &BTreeSet<&model::TableNameRef>,
+        n_persons: usize,
+        settings: &SolverSettings,
+    ) -> Vec<Infeasibility> {
+        let min_persons = settings.min_table_persons as usize;
+        let below_minimum = |t: usize| Infeasibility::TableBelowMinimum {
+            table: table_names[t].to_owned(),
+            seats: table_sizes[t],
+            min_persons: settings.min_table_persons,
+        };
+        let not_enough_persons = |n_tables: usize| Infeasibility::NotEnoughPersons {
+            persons: n_persons,
+            tables: n_tables,
+            min_persons: settings.min_table_persons,
+        };
+
+        if !settings.close_unused_tables {
+            let mut reasons = (0..table_sizes.len())
+                .filter(|t| (table_sizes[*t] as usize) < min_persons)
+                .map(below_minimum)
+                .collect::<Vec<_>>();
+            if table_sizes.len() * min_persons > n_persons {
+                reasons.push(not_enough_persons(table_sizes.len()));
+            }
+            return reasons;
+        }
+
+        // Open pinned tables, then the largest ones until everyone can be seated, tables are
+        // sorted by size
+        let mut open = table_names
+            .iter()
+            .map(|t| pinned.contains(t))
+            .collect::<Vec<_>>();
+        let mut n_open_seats: usize = (0..open.len())
+            .filter(|t| open[*t])
+            .map(|t| table_sizes[t] as usize)
+            .sum();
+        for t in (0..open.len()).rev() {
+            let usable = table_sizes[t] > 0 && (table_sizes[t] as usize) >= min_persons;
+            if !open[t] && usable && n_open_seats < n_persons {
+                n_open_seats += table_sizes[t] as usize;
+                open[t] = true;
+            }
+        }
+        let mut reasons = (0..open.len())
+            .filter(|t| open[*t] && (table_sizes[*t] as usize) < min_persons)
+            .map(below_minimum)
+            .collect::<Vec<_>>();
+        let n_open_tables = open.iter().filter(|o| **o).count();
+        if n_open_seats < n_persons {
+            // Only seats at tables that can reach the minimum are counted
+            reasons.push(Infeasibility::NotEnoughSeats {
+                persons: n_persons,
+                seats: n_open_seats,
+            });
+        } else if n_open_tables * min_persons > n_persons {
+            reasons.push(not_enough_persons(n_open_tables));
+        }
+        if !reasons.is_empty() {
+            return reasons;
+        }
+
+        for (size, open) in table_sizes.iter_mut().zip(open) {
+            if !open {
+                *size = 0;
+            }
+        }
+        reasons
+    }
+
+    /// The current assignment, with persons in order at every table as by
+    /// [`Solver::assignment_of`].
+    fn assignment(&self) -> model::Assignment {
+        let mut out = model::Assignment::new();
+        for (table_idx, persons_idx) in self.assignor.table_assignment() {
+            let table_name = self.mapping.table_name(table_idx).unwrap().to_owned();
+            let mut persons_idx = persons_idx.to_vec();
+            persons_idx.sort_unstable();
+            let person_names: Vec<_> = persons_idx
+                .iter()
+                .map(|p| self.mapping.person_name(*p).unwrap().to_owned())
+                .collect();
+            let prev = out.insert(table_name, person_names);
+            assert!(prev.is_none());
         }
         out
     }
 
-    pub fn solve(&mut self) -> SolverResult<model::Assignment> {
-        if self.assignor.seat_count() < self.assignor.person_count() {
-            return Err(SolverError::NoSolution(
-                "there is not enough sitting space".into(),
-            ));
+    /// The table of every person, if seated.
+    fn person_tables(&self) -> Vec</* PersonIdx, */ Option<TableIdx>> {
+        self.assignor
+            .persons()
+            .map(|p| self.assignor.person_table(p))
+            .collect()
+    }
+
+    /// The assignment seating persons at the given tables, in order at every table so that the
+    /// same seating always gives the same assignment.
+    fn assignment_of(&self, person_tables: &[Option<TableIdx>]) -> model::Assignment {
+        let mut seated = self
+            .assignor
+            .tables()
+            .map(|_| Vec::new())
+            .collect::<Vec<_>>();
+        for (person, table) in person_tables.iter().enumerate() {
+            if let Some(table) = table {
+                seated[*table as usize].push(self.person_name(person as PersonIdx));
+            }
         }
+        seated
+            .into_iter()
+            .enumerate()
+            .map(|(table, person_names)| (self.table_name(table as TableIdx), person_names))
+            .collect()
+    }
 
-        for person in self.assignor.persons() {
-            let (cost, table) = self
+    /// Persons seated together, in seating order.
+    ///
+    /// Pinned persons are seated first, with their group if it must stay together.
+    /// Other groups that must stay together are seated next, largest first, as they are the
+    /// hardest to fit.
+    /// Members of other groups are seated one after the other so that they can still end up
+    /// together, before persons without a group.
+    /// Within these constraints, groups and persons are seated in the given order.
+    fn seating_blocks(&self, order: SeatingOrder) -> Vec<SeatingBlock> {
+        let (required, mut others): (Vec<_>, Vec<_>) = self
+            .assignor
+            .groups()
+            .map(|(_, g)| (g.cohesion, g.members.clone()))
+            .partition(|(cohesion, _)| *cohesion == model::GroupCohesion::Require);
+        let mut alone = self
+            .assignor
+            .persons()
+            .filter(|p| self.assignor.person_group(*p).is_none())
+            .collect::<Vec<_>>();
+
+        match order {
+            SeatingOrder::Tribe | SeatingOrder::Previous => {}
+            SeatingOrder::MostConstrained => {
+                let relations: &RelationGraph = self.assignor.as_ref();
+                let degree = |persons: &[PersonIdx]| -> usize {
+                    persons
+                        .iter()
+                        .map(|p| relations.neighbors_slice(*p).len())
+                        .sum()
+                };
+                others.sort_by_key(|(_, members)| std::cmp::Reverse(degree(members)));
+                alone.sort_by_key(|p| std::cmp::Reverse(degree(&[*p])));
+            }
+            SeatingOrder::Random(mut rng) => {
+                rng.shuffle(&mut others);
+                for (_, members) in others.iter_mut() {
+                    rng.shuffle(members);
+                }
+                rng.shuffle(&mut alone);
+            }
+        }
+
+        let pinned_table =
+            |persons: &[PersonIdx]| persons.iter().find_map(|p| self.pinned_tables[*p as usize]);
+        let block = |persons: Vec<PersonIdx>| SeatingBlock {
+            table: pinned_table(&persons),
+            persons,
+        };
+        // Groups without attending members have nobody to seat
+        let mut blocks: Vec<SeatingBlock> = required
+            .into_iter()
+            .filter(|(_, m)| !m.is_empty())
+            .map(|(_, m)| block(m))
+            .collect();
+        blocks.extend(
+            others
+                .into_iter()
+                .flat_map(|(_, m)| m)
+                .map(|p| block(vec![p])),
+        );
+        blocks.extend(alone.into_iter().map(|p| block(vec![p])));
+        // Stable sort keeps the order of persons otherwise
+        blocks.sort_by_key(|b| {
+            let is_required_group = b.persons.len() > 1;
+            (
+                b.table.is_none(),
+                !is_required_group,
+                std::cmp::Reverse(b.persons.len()),
+            )
+        });
+        blocks
+    }
+
+    /// Check constraints that cannot be met whatever the assignment, reporting all of them.
+    fn check_feasible(&self) -> SolverResult<()> {
+        let mut reasons = self.table_reasons.clone();
+        let seats_reported = reasons
+            .iter()
+            .any(|r| matches!(r, Infeasibility::NotEnoughSeats { .. }));
+        if !seats_reported && self.assignor.seat_count() < self.assignor.person_count() {
+            reasons.push(Infeasibility::NotEnoughSeats {
+                persons: self.assignor.person_count() as usize,
+                seats: self.assignor.seat_count() as usize,
+            });
+        }
+
+        let largest_table = self
+            .assignor
+            .tables()
+            .map(|t| self.assignor.table_seat_count(t))
+            .max()
+            .unwrap_or(0);
+        for (g, group) in self.assignor.groups() {
+            if group.cohesion != model::GroupCohesion::Require {
+                continue;
+            }
+            let group_name = self.mapping.group_name(g).unwrap();
+            if group.members.len() as Size > largest_table {
+                reasons.push(Infeasibility::GroupTooLarge {
+                    group: group_name.to_owned(),
+                    members: group.members.len(),
+                    largest_table,
+                });
+            }
+            let pinned = group
+                .members
+                .iter()
+                .filter_map(|p| self.pinned_tables[*p as usize])
+                .collect::<BTreeSet<_>>();
+            if pinned.len() > 1 {
+                reasons.push(Infeasibility::GroupPinnedApart {
+                    group: group_name.to_owned(),
+                    tables: pinned.into_iter().map(|t| self.table_name(t)).collect(),
+                });
+            }
+        }
+
+        let mut pinned_persons = vec![Vec::new(); self.assignor.table_count() as usize];
+        for block in self.seating_blocks(SeatingOrder::Tribe) {
+            if let Some(t) = block.table {
+                pinned_persons[t as usize].extend(block.persons);
+            }
+        }
+        for (t, persons) in pinned_persons.into_iter().enumerate() {
+            let seats = self.assignor.table_seat_count(t as TableIdx);
+            if persons.len() as Size > seats {
+                reasons.push(Infeasibility::TableOverfilledByPins {
+                    table: self.table_name(t as TableIdx),
+                    seats,
+                    pinned: persons.into_iter().map(|p| self.person_name(p)).collect(),
+                });
+            }
+        }
+
+        check_reasons(reasons)
+    }
+
+    fn table_name(&self, table: TableIdx) -> model::TableName {
+        self.mapping.table_name(table).unwrap().to_owned()
+    }
+
+    fn person_name(&self, person: PersonIdx) -> model::PersonName {
+        self.mapping.person_name(person).unwrap().to_owned()
+    }
+
+    /// Record the persons seated together in a previous assignment, before solving.
+    fn add_met_pairs(&mut self, assignment: &model::Assignment) {
+        for persons in assignment.values() {
+            let persons = persons
+                .iter()
+                .filter_map(|p| self.mapping.person_idx(p))
+                .collect::<Vec<_>>();
+            for (i, p1) in persons.iter().enumerate() {
+                for p2 in persons.iter().skip(i + 1) {
+                    self.assignor.add_met_pair(*p1, *p2);
+                }
+            }
+        }
+    }
+
+    /// Record where persons were seated in a previous assignment, before solving.
+    fn set_previous(&mut self, assignment: &model::Assignment) {
+        for (table_name, persons) in assignment.iter() {
+            let Some(table) = self.mapping.table_idx(table_name) else {
+                continue;
+            };
+            for person in persons.iter().filter_map(|p| self.mapping.person_idx(p)) {
+                self.assignor.set_previous_table(person, table);
+            }
+        }
+    }
+
+    /// Seat persons back at their previous table, as far as it has room for them. Groups that
+    /// must stay together are only seated back if they all were at the same table.
+    fn seat_previous(&mut self) {
+        for block in self.seating_blocks(SeatingOrder::Previous) {
+            let tables = block
+                .persons
+                .iter()
+                .map(|p| self.assignor.previous_table(*p))
+                .collect::<BTreeSet<_>>();
+            let [Some(table)] = tables.into_iter().collect::<Vec<_>>()[..] else {
+                continue;
+            };
+            if block.table.is_some_and(|pinned| pinned != table)
+                || self.assignor.table_free_seat_count(table) < block.persons.len() as Size
+            {
+                continue;
+            }
+            for person in block.persons {
+                let cost = self.assignor.assignment_cost(person, table);
+                self.assignor.assign_with_cost(person, table, cost);
+            }
+        }
+    }
+
+    /// Seat persons as in the given assignment, as far as they and their table exist.
+    fn seat(&mut self, assignment: &model::Assignment) {
+        for (table_name, persons) in assignment.iter() {
+            let Some(table) = self.mapping.table_idx(table_name) else {
+                continue;
+            };
+            for person in persons.iter().filter_map(|p| self.mapping.person_idx(p)) {
+                let cost = self.assignor.assignment_cost(person, table);
+                self.assignor.assign_with_cost(person, table, cost);
+            }
+        }
+    }
+
+    fn score(&mut self) -> Score {
+        Score {
+            total_cost: self.assignor.total_cost(),
+            worst_person: self
+                .assignor
+                .worst_person()
+                .map(|(p, cost)| (self.mapping.person_name(p).unwrap().to_owned(), cost)),
+            lower_bound: self.assignor.lower_bound(),
+        }
+    }
+
+    fn objective_value(&mut self) -> ObjectiveValue {
+        let total = self.assignor.total_cost();
+        let mut worst = || self.assignor.worst_person().map(|(_, c)| c).unwrap_or(0.0);
+        match self.settings.objective {
+            Objective::Total => ObjectiveValue {
+                primary: total,
+                secondary: 0.0,
+            },
+            Objective::Fairness => ObjectiveValue {
+                primary: worst(),
+                secondary: total,
+            },
+            Objective::Blend(weight) => ObjectiveValue {
+                primary: total + weight * worst(),
+                secondary: 0.0,
+            },
+        }
+    }
+
+    /// Improve the current assignment by moving and swapping persons until no such change
+    /// improves the objective.
+    ///
+    /// Pinned persons and members of groups that must stay together are not moved, and
+    /// persons are not moved away from tables at the minimum occupancy.
+    /// Stops early, keeping the best assignment, when the monitor is stopped.
+    async fn improve(&mut self, monitor: &mut Monitor<'_>) {
+        let movable = self.movable_persons();
+
+        let mut best = self.objective_value();
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for &person in movable.iter() {
+                monitor.pause().await;
+                if monitor.is_stopped() {
+                    break;
+                }
+                for table in self.assignor.tables() {
+                    // Person may have moved in a previous iteration
+                    let current = self.assignor.person_table(person).unwrap();
+                    if table == current {
+                        continue;
+                    }
+
+                    if !self.assignor.table_is_full(table) && self.can_leave(person) {
+                        self.assignor.reassign_with_cost(person, table);
+                        let value = self.objective_value();
+                        monitor.step(best.primary);
+                        if value.improves_on(&best) {
+                            best = value;
+                            improved = true;
+                            continue;
+                        }
+                        self.assignor.reassign_with_cost(person, current);
+                    }
+
+                    let others = self.assignor.table_persons(table).to_vec();
+                    for other in others.into_iter().filter(|o| movable.contains(o)) {
+                        self.assignor.swap_with_cost(person, other);
+                        let value = self.objective_value();
+                        monitor.step(best.primary);
+                        if value.improves_on(&best) {
+                            best = value;
+                            improved = true;
+                            break;
+                        }
+                        self.assignor.swap_with_cost(person, other);
+                    }
+                }
+            }
+        }
+        monitor.report(best.primary);
+    }
+
+    /// Persons that are neither pinned nor in a group that must stay together.
+    fn movable_persons(&self) -> Vec<PersonIdx> {
+        self.assignor
+            .persons()
+            .filter(|p| self.pinned_tables[*p as usize].is_none())
+            .filter(|p| {
+                self.assignor
+                    .person_group(*p)
+                    .is_none_or(|g| g.cohesion != model::GroupCohesion::Require)
+            })
+            .collect()
+    }
+
+    /// Shake the best assignment with random swaps and improve it again, until the monitor
+    /// is stopped or no person can move to another table.
+    async fn explore(&mut self, monitor: &mut Monitor<'_>) {
+        let movable = self.movable_persons();
+        if movable.len() < 2 {
+            return;
+        }
+        let n_swaps = 1 + movable.len() / 8;
+
+        let mut best = self.assignor.clone();
+        let mut best_value = self.objective_value();
+        while !monitor.is_stopped() {
+            for _ in 0..n_swaps {
+                let person1 = *self.rng.choose(&movable).unwrap();
+                let person2 = *self.rng.choose(&movable).unwrap();
+                if self.assignor.person_table(person1) != self.assignor.person_table(person2) {
+                    self.assignor.swap_with_cost(person1, person2);
+                }
+            }
+            let iteration = monitor.iteration();
+            self.improve(monitor).await;
+
+            let value = self.objective_value();
+            self.alternatives.add(self.candidate(value));
+            // Compared as alternatives are, for the best one to be the assignment found
+            if value.rank(&best_value).is_lt() {
+                best.clone_from(&self.assignor);
+                best_value = value;
+            } else {
+                self.assignor.clone_from(&best);
+            }
+            // Without any change to evaluate, the iterations would never reach their limit
+            if monitor.iteration() == iteration {
+                break;
+            }
+        }
+    }
+
+    /// Persons missing for an open table to reach the minimum.
+    fn table_min_deficit(&self, table: TableIdx) -> Size {
+        if self.assignor.table_seat_count(table) == 0 {
+            0
+        } else {
+            self.settings
+                .min_table_persons
+                .saturating_sub(self.assignor.table_person_count(table))
+        }
+    }
+
+    /// Whether a person can leave their table without it going under the minimum.
+    fn can_leave(&self, person: PersonIdx) -> bool {
+        self.assignor
+            .person_table(person)
+            .is_some_and(|t| self.assignor.table_person_count(t) > self.settings.min_table_persons)
+    }
+
+    /// Block until solved, within the time and iteration budgets of the settings, see
+    /// [`Solver::solve_monitored`] in browsers.
+    pub fn solve(&mut self) -> SolverResult<model::Assignment> {
+        let mut monitor = Monitor::new();
+        monitor.set_deadline(self.settings.time_budget);
+        monitor.set_iteration_limit(self.settings.iteration_budget);
+        block_on(self.solve_monitored(&mut monitor))
+    }
+
+    /// Build an assignment, from the current one where nobody is seated, by seating persons one
+    /// after the other at the cheapest table that leaves room for the others.
+    ///
+    /// Pauses between persons, as it takes long on its own for large problems.
+    async fn seat_greedily(
+        &mut self,
+        order: SeatingOrder,
+        monitor: &mut Monitor<'_>,
+    ) -> SolverResult<()> {
+        if let SeatingOrder::Previous = order {
+            self.seat_previous();
+        }
+        let mut n_unseated = self
+            .assignor
+            .persons()
+            .filter(|p| !self.assignor.person_is_seated(*p))
+            .count() as Size;
+        for block in self.seating_blocks(order) {
+            monitor.pause().await;
+            if self.assignor.person_is_seated(block.persons[0]) {
+                continue;
+            }
+            let block_size = block.persons.len() as Size;
+            let total_deficit: Size = self
+                .assignor
+                .tables()
+                .map(|t| self.table_min_deficit(t))
+                .sum();
+            let (_, table) = self
                 .assignor
                 .tables()
-                .filter(|t| !self.assignor.table_is_full(*t))
-                .map(|t| (self.assignor.assignment_cost(person, t), t))
+                .filter(|t| block.table.is_none_or(|required| required == *t))
+                .filter(|t| self.assignor.table_free_seat_count(*t) >= block_size)
+                // Keep enough persons to reach the minimum at other tables
+                .filter(|t| {
+                    let deficit = total_deficit - self.table_min_deficit(*t).min(block_size);
+                    deficit <= n_unseated - block_size
+                })
+                .map(|t| {
+                    let cost: Cost = block
+                        .persons
+                        .iter()
+                        .map(|p| self.assignor.assignment_cost(*p, t))
+                        .sum();
+                    (cost, t)
+                })
                 .min_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap_or((0.0, 0));
+                .ok_or_else(|| {
+                    SolverError::NotSeated(
+                        block.persons.iter().map(|p| self.person_name(*p)).collect(),
+                    )
+                })?;
+
+            for person in block.persons {
+                let cost = self.assignor.assignment_cost(person, table);
+                self.assignor.assign_with_cost(person, table, cost);
+            }
+            n_unseated -= block_size;
+        }
+        Ok(())
+    }
+
+    /// Number of starts of a multi-start solve, enough to offer all alternatives.
+    fn n_starts(&self) -> u32 {
+        self.settings.starts.max(self.settings.alternatives).max(1)
+    }
+
+    /// The current assignment, to offer as an alternative.
+    fn candidate(&self, value: ObjectiveValue) -> Candidate {
+        Candidate {
+            value,
+            person_tables: self.person_tables(),
+        }
+    }
+
+    /// The best distinct assignments found by the last solve, best first.
+    fn alternatives(&self) -> Vec<Alternative> {
+        self.alternatives
+            .kept
+            .iter()
+            .map(|c| Alternative {
+                assignment: self.assignment_of(&c.person_tables),
+                cost: c.value.primary,
+            })
+            .collect()
+    }
+
+    /// The order of persons of the given start of a multi-start solve.
+    fn seating_order(&self, start: u32) -> SeatingOrder {
+        let has_previous = self
+            .assignor
+            .persons()
+            .any(|p| self.assignor.previous_table(p).is_some());
+        match start {
+            0 if has_previous => SeatingOrder::Previous,
+            0 => SeatingOrder::Tribe,
+            1 => SeatingOrder::MostConstrained,
+            // Every start has its own generator to give the same orders when run in parallel
+            _ => SeatingOrder::Random(Rng::new(self.settings.seed).split(start.into())),
+        }
+    }
 
-            self.assignor.assign_with_cost(person, table, cost);
+    /// Build and improve an assignment from scratch for each of the given starts, one after
+    /// the other, until the monitor is stopped.
+    ///
+    /// Every start evaluates at most `start_budget` assignments, if given.
+    async fn run_starts(
+        &mut self,
+        starts: impl Iterator<Item = u32>,
+        start_budget: Option<u64>,
+        monitor: &mut Monitor<'_>,
+    ) -> BestStart {
+        let unseated = self.assignor.clone();
+        let iteration_limit = monitor.iteration_limit();
+        let mut best = BestStart::default();
+        for (i, start) in starts.enumerate() {
+            monitor.pause().await;
+            if i > 0 && monitor.is_stopped() {
+                break;
+            }
+            let start_limit = start_budget.map(|budget| monitor.iteration() + budget);
+            monitor.set_iteration_limit(start_limit);
+            self.assignor.clone_from(&unseated);
+            let order = self.seating_order(start);
+            let mut seated = self.seat_greedily(order.clone(), monitor).await;
+            if seated.is_err() && matches!(order, SeatingOrder::Previous) {
+                // The persons seated back may leave no room to seat the others
+                self.assignor.clone_from(&unseated);
+                seated = self.seat_greedily(SeatingOrder::Tribe, monitor).await;
+            }
+            let result = match seated {
+                Ok(()) => {
+                    self.improve(monitor).await;
+                    Ok((self.assignor.clone(), self.objective_value()))
+                }
+                Err(err) => Err(err),
+            };
+            if let Ok((_, value)) = &result {
+                best.candidates.push((start, self.candidate(*value)));
+            }
+            best.add(start, result);
+            monitor.set_iteration_limit(iteration_limit);
         }
+        self.assignor = unseated;
+        best
+    }
 
+    /// Assignments every start may evaluate, an equal share of what is left in the round, so
+    /// that starts give the same assignments however they are spread over threads.
+    fn start_budget(&self, monitor: &Monitor) -> Option<u64> {
+        let remaining = monitor
+            .iteration_limit()?
+            .saturating_sub(monitor.iteration());
+        Some(remaining / u64::from(self.n_starts()))
+    }
+
+    /// Same as [`Solver::run_starts`] for all starts, spread over threads.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    async fn run_all_starts(&mut self, monitor: &mut Monitor<'_>) -> BestStart {
+        let starts = self.n_starts();
+        let threads = match self.settings.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n as usize,
+        }
+        .min(starts as usize);
+        let start_budget = self.start_budget(monitor);
+        if threads <= 1 {
+            return self.run_starts(0..starts, start_budget, monitor).await;
+        }
+
+        let iteration = monitor.iteration();
+        let (progress_sender, progress) = std::sync::mpsc::channel();
+        let workers = std::thread::scope(|scope| {
+            let handles = (0..threads)
+                .map(|w| {
+                    let mut solver = self.clone();
+                    let mut worker = monitor.worker(w, &progress_sender);
+                    scope.spawn(move || {
+                        let starts = (w as u32..starts).step_by(threads);
+                        let best = block_on(solver.run_starts(starts, start_budget, &mut worker));
+                        (best, worker.iteration() - iteration)
+                    })
+                })
+                .collect::<Vec<_>>();
+            // Following ends once the workers, holding the other senders, are done
+            drop(progress_sender);
+            monitor.follow_workers(progress);
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("solver thread panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        let mut best = BestStart::default();
+        for (worker_best, iterations) in workers {
+            best.merge(worker_best);
+            monitor.add_iterations(iterations);
+        }
+        if let Some((_, _, value)) = &best.best {
+            monitor.report(value.primary);
+        }
+        best
+    }
+
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    async fn run_all_starts(&mut self, monitor: &mut Monitor<'_>) -> BestStart {
+        let start_budget = self.start_budget(monitor);
+        self.run_starts(0..self.n_starts(), start_budget, monitor)
+            .await
+    }
+
+    /// Seat everyone, improving the best of [`SolverSettings::starts`] assignments.
+    pub async fn solve_monitored(
+        &mut self,
+        monitor: &mut Monitor<'_>,
+    ) -> SolverResult<model::Assignment> {
+        self.check_feasible()?;
+        let mut starts = self.run_all_starts(monitor).await;
+        for candidate in starts.sorted_candidates() {
+            self.alternatives.add(candidate);
+        }
+        self.assignor = starts.into_result()?;
+
+        if monitor.is_limited() {
+            self.explore(monitor).await;
+        }
         Ok(self.assignment())
     }
 }
@@ -390,7 +2127,10 @@ impl<'pb> Solver<'pb> {
 mod tests {
     use std::collections::HashSet;
 
+    use std::time::Duration;
+
     use super::super::examples;
+    use super::super::monitor::{CancelToken, Stopwatch};
     use super::*;
 
     fn assign_in_order(assignor: &mut Assignor) {
@@ -453,6 +2193,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_assignor_unassign() {
+        let tables = vec![2, 3];
+        let n_persons: Size = 5;
+        let mut assignor = Assignor::from_table_sizes(tables.clone(), n_persons);
+        assign_in_order(&mut assignor);
+
+        assert!(assignor.unassign(0));
+        assert!(!assignor.unassign(0));
+        assert_eq!(assignor.person_table(0), None);
+        assert_eq!(assignor.table_persons(0), &[1]);
+        assert!(!assignor.table_is_full(0));
+
+        assert!(assignor.unassign(3));
+        assert_eq!(assignor.table_persons(1), &[2, 4]);
+        assert!(assignor.assign(0, 1));
+        assert_eq!(assignor.person_table(0), Some(1));
+        assert_eq!(assignor.table_persons(1), &[2, 4, 0]);
+    }
+
     #[test]
     fn test_assignor_with_costs() {
         let tables = vec![2, 4];
@@ -510,13 +2270,7 @@ mod tests {
     #[test]
     fn test_solver_empty() -> SolverResult<()> {
         let (tribe, tables) = examples::empty();
-        let mut solver = Solver::new(
-            &tables,
-            &tribe,
-            SolverSettings {
-                relation_values: [-4.0, -1.0, 1.0, 4.0],
-            },
-        )?;
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
         let assignment = solver.solve()?;
 
         assert!(assignment.is_empty());
@@ -527,13 +2281,7 @@ mod tests {
     #[test]
     fn test_solver_harry_potter() -> SolverResult<()> {
         let (tribe, tables) = examples::harry_potter();
-        let mut solver = Solver::new(
-            &tables,
-            &tribe,
-            SolverSettings {
-                relation_values: [-4.0, -1.0, 1.0, 4.0],
-            },
-        )?;
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
         let assignment = solver.solve()?;
 
         assert_eq!(assignment.len(), tables.len());
@@ -552,4 +2300,1087 @@ mod tests {
 
         Ok(())
     }
+
+    fn table_of<'a>(assignment: &'a model::Assignment, person: &str) -> &'a str {
+        assignment
+            .iter()
+            .find(|(_, persons)| persons.iter().any(|p| p == person))
+            .map(|(t, _)| t.as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_solver_groups() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.add_group("Weasley", None, model::GroupCohesion::Require);
+        for p in [
+            "Ron Weasley",
+            "Ginny Weasley",
+            "Percy Weasley",
+            "George Weasley",
+        ] {
+            tribe.add_to_group("Weasley", p);
+        }
+        tribe.add_group(
+            "Aurors",
+            Some(RelationStrength::Loves),
+            model::GroupCohesion::Free,
+        );
+        tribe.add_to_group("Aurors", "Kingsley Shacklebolt");
+        tribe.add_to_group("Aurors", "Nymphadora Tonks");
+
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        let assignment = solver.solve()?;
+
+        let weasley_table = table_of(&assignment, "Ron Weasley");
+        for p in tribe.group("Weasley").unwrap().members() {
+            assert_eq!(table_of(&assignment, p), weasley_table);
+        }
+        // The group relation is used as any other relation
+        assert_eq!(
+            table_of(&assignment, "Kingsley Shacklebolt"),
+            table_of(&assignment, "Nymphadora Tonks"),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_groups_empty() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.add_group("New", None, model::GroupCohesion::Require);
+        let assignment = solve(&tables, &tribe, SolverSettings::default())?;
+        assert_eq!(assignment.values().flatten().count(), tribe.persons_count());
+
+        // Members that do not attend leave the group empty
+        tribe.add_group("Away", None, model::GroupCohesion::Require);
+        let persons = tribe.persons().take(2).cloned().collect::<Vec<_>>();
+        for p in persons.iter() {
+            tribe.add_to_group("Away", p.clone());
+            tribe.set_absent(p, true);
+        }
+        let assignment = solve(&tables, &tribe, SolverSettings::default())?;
+        assert_eq!(
+            assignment.values().flatten().count(),
+            tribe.persons_count() - persons.len()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_groups_too_large() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.add_group("Everyone", None, model::GroupCohesion::Require);
+        let persons = tribe.persons().cloned().collect::<Vec<_>>();
+        for p in persons {
+            tribe.add_to_group("Everyone", p);
+        }
+
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        assert!(matches!(solver.solve(), Err(SolverError::NoSolution(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_groups_prefer() -> SolverResult<()> {
+        let mut tribe = model::Tribe::new();
+        for p in ["A", "B", "C", "D"] {
+            tribe.add_person(p);
+        }
+        tribe.add_group("AD", None, model::GroupCohesion::Prefer);
+        tribe.add_to_group("AD", "A");
+        tribe.add_to_group("AD", "D");
+        let tables = model::Tables::from_iter([
+            ("1".to_owned(), model::TableType { n_seats: 2 }),
+            ("2".to_owned(), model::TableType { n_seats: 2 }),
+        ]);
+
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        let assignment = solver.solve()?;
+        assert_eq!(table_of(&assignment, "A"), table_of(&assignment, "D"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_worst_person_cached() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        let persons = tribe.persons().take(4).cloned().collect::<Vec<_>>();
+        tribe.add_group("Prefer", None, model::GroupCohesion::Prefer);
+        for person in persons {
+            tribe.add_to_group("Prefer", person);
+        }
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        block_on(solver.seat_greedily(SeatingOrder::Tribe, &mut Monitor::new()))?;
+
+        let persons = solver.assignor.persons().collect::<Vec<_>>();
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let person1 = *rng.choose(&persons).unwrap();
+            let person2 = *rng.choose(&persons).unwrap();
+            solver.assignor.swap_with_cost(person1, person2);
+            // Same worst cost as evaluating every person again
+            let scanned = persons
+                .iter()
+                .map(|p| solver.assignor.person_cost(*p))
+                .max_by(|a, b| a.partial_cmp(b).unwrap());
+            let cached = solver.assignor.worst_person().map(|(_, cost)| cost);
+            assert_eq!(cached, scanned);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_goal_shortfall() {
+        let at_least = Goal::AtLeast {
+            holders: vec![true, false, true],
+            count: 2,
+        };
+        assert_eq!(at_least.shortfall([].into_iter()), 2);
+        assert_eq!(at_least.shortfall([0, 1].into_iter()), 1);
+        assert_eq!(at_least.shortfall([0, 1, 2].into_iter()), 0);
+
+        let mix = Goal::Mix {
+            values: vec![vec![0], vec![0, 1], vec![], vec![2]],
+            value_count: 3,
+        };
+        assert_eq!(mix.shortfall([2].into_iter()), 3);
+        assert_eq!(mix.shortfall([0, 1].into_iter()), 1);
+        assert_eq!(mix.shortfall([1, 3].into_iter()), 0);
+    }
+
+    #[test]
+    fn test_solver_attribute_goals() -> SolverResult<()> {
+        let french = model::Attribute::new("Language", "French");
+        let mut tribe = model::Tribe::new();
+        for p in ["A", "B", "C", "D", "E", "F"] {
+            tribe.add_person(p);
+        }
+        tribe.add_attribute("A", french.clone());
+        tribe.add_attribute("B", french.clone());
+        for (p, side) in [("A", "Bride"), ("B", "Bride"), ("C", "Bride")] {
+            tribe.add_attribute(p, model::Attribute::new("Side", side));
+        }
+        for (p, side) in [("D", "Groom"), ("E", "Groom"), ("F", "Groom")] {
+            tribe.add_attribute(p, model::Attribute::new("Side", side));
+        }
+        let tables = model::Tables::from_iter([
+            ("1".to_owned(), model::TableType { n_seats: 3 }),
+            ("2".to_owned(), model::TableType { n_seats: 3 }),
+        ]);
+        let settings = SolverSettings {
+            attribute_goals: vec![
+                model::AttributeGoal::AtLeast {
+                    attribute: french.clone(),
+                    count: 1,
+                },
+                model::AttributeGoal::Mix {
+                    name: "Side".into(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut solver = Solver::new(&tables, &tribe, settings)?;
+        let assignment = solver.solve()?;
+        for persons in assignment.values() {
+            assert!(persons.iter().any(|p| tribe.has_attribute(p, &french)));
+            assert_eq!(
+                persons
+                    .iter()
+                    .flat_map(|p| tribe.person_attributes(p))
+                    .filter(|a| a.name == "Side")
+                    .map(|a| a.value.as_str())
+                    .collect::<BTreeSet<_>>(),
+                BTreeSet::from(["Bride", "Groom"]),
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assignor_with_costs_moves() {
+        let n_persons: Size = 4;
+        let assignor = Assignor::from_table_sizes(vec![2, 3], n_persons);
+        let mut relations = RelationGraph::with_nodes(n_persons as usize);
+        relations.add_edge(0, 1, RelationStrength::Loves);
+        relations.add_edge(0, 2, RelationStrength::Hates);
+        relations.add_edge(2, 3, RelationStrength::Likes);
+        let mut assignor = AssignorWithCosts::new(assignor, relations, [4.0, 1.0, -1.0, -4.0]);
+
+        for (p, t) in [(0, 0), (2, 0), (1, 1), (3, 1)] {
+            let cost = assignor.assignment_cost(p, t);
+            assert!(assignor.assign_with_cost(p, t, cost));
+        }
+        assert_eq!(assignor.total_cost(), 4.0);
+        assert_eq!(assignor.person_cost(0), 4.0);
+        assert_eq!(assignor.person_cost(3), 0.0);
+
+        assert!(assignor.swap_with_cost(2, 1));
+        assert_eq!(assignor.total_cost(), -5.0);
+        assert_eq!(assignor.person_cost(2), -1.0);
+
+        assert!(assignor.reassign_with_cost(0, 1));
+        assert_eq!(assignor.total_cost(), 3.0);
+        assert!(!assignor.reassign_with_cost(1, 1));
+        assert!(assignor.unassign_with_cost(0));
+        assert_eq!(assignor.total_cost(), -1.0);
+        assert_eq!(assignor.person_cost(0), 0.0);
+    }
+
+    fn fairness_problem() -> (model::Tribe, model::Tables) {
+        let mut tribe = model::Tribe::new();
+        tribe.add_relation("A", "B", RelationStrength::Loves);
+        tribe.add_relation("X", "C", RelationStrength::Dislikes);
+        tribe.add_relation("A", "C", RelationStrength::Likes);
+        tribe.add_relation("B", "X", RelationStrength::Likes);
+        let tables = model::Tables::from_iter([
+            ("1".to_owned(), model::TableType { n_seats: 2 }),
+            ("2".to_owned(), model::TableType { n_seats: 2 }),
+        ]);
+        (tribe, tables)
+    }
+
+    #[test]
+    fn test_solver_objectives() -> SolverResult<()> {
+        let (tribe, tables) = fairness_problem();
+
+        let settings = SolverSettings::default();
+        let assignment = solve(&tables, &tribe, settings.clone())?;
+        let total_score = score(&tables, &tribe, settings, &assignment)?;
+        assert_eq!(table_of(&assignment, "A"), table_of(&assignment, "B"));
+        assert_eq!(total_score.total_cost, -3.0);
+        assert_eq!(total_score.worst_person.unwrap().1, 1.0);
+
+        let settings = SolverSettings {
+            objective: Objective::Fairness,
+            ..Default::default()
+        };
+        let assignment = solve(&tables, &tribe, settings.clone())?;
+        let fair_score = score(&tables, &tribe, settings, &assignment)?;
+        assert_eq!(table_of(&assignment, "A"), table_of(&assignment, "C"));
+        assert_eq!(fair_score.total_cost, -2.0);
+        assert_eq!(fair_score.worst_person.unwrap().1, -1.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_score() -> SolverResult<()> {
+        let (tribe, tables) = fairness_problem();
+        let assignment = model::Assignment::from_iter([
+            ("1".to_owned(), vec!["A".to_owned(), "X".to_owned()]),
+            ("2".to_owned(), vec!["B".to_owned(), "C".to_owned()]),
+            ("Unknown".to_owned(), vec!["D".to_owned()]),
+        ]);
+        let assignment_score = score(&tables, &tribe, SolverSettings::default(), &assignment)?;
+        assert_eq!(assignment_score.total_cost, 0.0);
+        assert_eq!(assignment_score.worst_person.unwrap().1, 0.0);
+
+        let assignment_score = score(
+            &tables,
+            &tribe,
+            SolverSettings::default(),
+            &model::Assignment::new(),
+        )?;
+        assert_eq!(assignment_score.total_cost, 0.0);
+        assert_eq!(assignment_score.worst_person, None);
+
+        Ok(())
+    }
+
+    fn tribe_of(n_persons: usize) -> model::Tribe {
+        let mut tribe = model::Tribe::new();
+        for p in 0..n_persons {
+            tribe.add_person(format!("{p:02}"));
+        }
+        tribe
+    }
+
+    #[test]
+    fn test_solver_min_table_persons() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+
+        let settings = SolverSettings {
+            min_table_persons: 6,
+            ..Default::default()
+        };
+        let assignment = solve(&tables, &tribe, settings)?;
+        for persons in assignment.values() {
+            assert!(persons.len() >= 6);
+        }
+
+        let settings = SolverSettings {
+            min_table_persons: 7,
+            ..Default::default()
+        };
+        assert!(matches!(
+            solve(&tables, &tribe, settings),
+            Err(SolverError::NoSolution(_))
+        ));
+
+        let settings = SolverSettings {
+            min_table_persons: 6,
+            ..Default::default()
+        };
+        assert!(matches!(
+            solve(&tables, &tribe_of(20), settings),
+            Err(SolverError::NoSolution(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_close_unused_tables() -> SolverResult<()> {
+        let (_, tables) = examples::harry_potter();
+
+        let settings = SolverSettings {
+            close_unused_tables: true,
+            ..Default::default()
+        };
+        let assignment = solve(&tables, &tribe_of(10), settings)?;
+        assert_eq!(assignment["Phoenix"].len(), 10);
+        assert!(assignment["Moonstones"].is_empty());
+        assert!(assignment["Willow"].is_empty());
+        assert!(assignment["Niffler"].is_empty());
+
+        let settings = SolverSettings {
+            close_unused_tables: true,
+            min_table_persons: 6,
+            ..Default::default()
+        };
+        let assignment = solve(&tables, &tribe_of(14), settings.clone())?;
+        assert!(assignment["Phoenix"].len() >= 6);
+        assert!(assignment["Moonstones"].len() >= 6);
+        assert!(assignment["Willow"].is_empty());
+        assert!(assignment["Niffler"].is_empty());
+
+        assert!(matches!(
+            solve(&tables, &tribe_of(5), settings),
+            Err(SolverError::NoSolution(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_fill_balance() -> SolverResult<()> {
+        let tables = model::Tables::from_iter([
+            ("Large".to_owned(), model::TableType { n_seats: 12 }),
+            ("Small".to_owned(), model::TableType { n_seats: 4 }),
+        ]);
+        let settings = SolverSettings {
+            fill_balance_value: 1.0,
+            ..Default::default()
+        };
+        let assignment = solve(&tables, &tribe_of(8), settings)?;
+        assert_eq!(assignment["Large"].len(), 6);
+        assert_eq!(assignment["Small"].len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_rounds() -> SolverResult<()> {
+        let tables = model::Tables::from_iter([
+            ("1".to_owned(), model::TableType { n_seats: 2 }),
+            ("2".to_owned(), model::TableType { n_seats: 2 }),
+        ]);
+        let settings = SolverSettings {
+            rounds: 3,
+            ..Default::default()
+        };
+        let rounds = solve_rounds(&tables, &tribe_of(4), settings)?;
+        assert_eq!(rounds.len(), 3);
+
+        let met = rounds
+            .iter()
+            .flat_map(|a| a.values())
+            .map(|persons| {
+                let mut persons = persons.clone();
+                persons.sort();
+                persons
+            })
+            .collect::<HashSet<_>>();
+        // Every pair of persons is seated together exactly once
+        assert_eq!(met.len(), 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        let settings = SolverSettings {
+            move_value: 100.0,
+            ..Default::default()
+        };
+        let previous = solve(&tables, &tribe, settings.clone())?;
+
+        let assignment = resolve(&tables, &tribe, settings.clone(), &previous)?;
+        assert!(model::moved_persons(&previous, &assignment).is_empty());
+
+        tribe.remove_person("Harry Potter");
+        tribe.add_person("Dobby");
+        let assignment = resolve(&tables, &tribe, settings.clone(), &previous)?;
+        assert!(model::moved_persons(&previous, &assignment).is_empty());
+        assert!(assignment.values().flatten().any(|p| p == "Dobby"));
+
+        let rounds = resolve_rounds(&tables, &tribe, settings, std::slice::from_ref(&previous))?;
+        assert!(model::moved_persons(&previous, &rounds[0]).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_seat_previous() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        let previous = solve(&tables, &tribe, SolverSettings::default())?;
+
+        // A newcomer is seated around the others, before any local search
+        tribe.add_person("Dobby");
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        solver.set_previous(&previous);
+        assert!(matches!(solver.seating_order(0), SeatingOrder::Previous));
+        block_on(solver.seat_greedily(SeatingOrder::Previous, &mut Monitor::new()))?;
+        let assignment = solver.assignment();
+        assert!(model::moved_persons(&previous, &assignment).is_empty());
+        assert!(assignment.values().flatten().any(|p| p == "Dobby"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_pins() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.add_group("Weasley", None, model::GroupCohesion::Require);
+        tribe.add_to_group("Weasley", "Ron Weasley");
+        tribe.add_to_group("Weasley", "Ginny Weasley");
+
+        let pin = |pins: &[(&str, &str)]| SolverSettings {
+            pins: pins
+                .iter()
+                .map(|(p, t)| (p.to_string(), t.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        let settings = pin(&[
+            ("Harry Potter", "Niffler"),
+            ("Ron Weasley", "Willow"),
+            ("Nobody", "Phoenix"),
+            ("Hermione Granger", "Nowhere"),
+        ]);
+        let assignment = solve(&tables, &tribe, settings)?;
+        assert_eq!(table_of(&assignment, "Harry Potter"), "Niffler");
+        assert_eq!(table_of(&assignment, "Ron Weasley"), "Willow");
+        assert_eq!(table_of(&assignment, "Ginny Weasley"), "Willow");
+
+        let settings = pin(&[("Ron Weasley", "Willow"), ("Ginny Weasley", "Niffler")]);
+        assert!(matches!(
+            solve(&tables, &tribe, settings),
+            Err(SolverError::NoSolution(_))
+        ));
+
+        let settings = SolverSettings {
+            close_unused_tables: true,
+            ..pin(&[("00", "Niffler")])
+        };
+        let assignment = solve(&tables, &tribe_of(3), settings)?;
+        // The pinned table is kept open, and is enough for everyone
+        assert_eq!(table_of(&assignment, "00"), "Niffler");
+        assert!(assignment["Phoenix"].is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_locks() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let previous = solve(&tables, &tribe, SolverSettings::default())?;
+
+        let locks = previous
+            .iter()
+            .flat_map(|(t, persons)| persons.iter().map(move |p| (p.clone(), t.clone())))
+            .filter(|(p, _)| p != "Harry Potter" && p != "Ron Weasley")
+            .collect::<model::Pins>();
+        let settings = SolverSettings {
+            locks,
+            // The seat freed by Harry Potter
+            pins: model::Pins::from_iter([(
+                "Luna Lovegood".to_owned(),
+                table_of(&previous, "Harry Potter").to_owned(),
+            )]),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.fixed_tables()["Luna Lovegood"],
+            table_of(&previous, "Harry Potter")
+        );
+
+        let assignment = solve(&tables, &tribe, settings.clone())?;
+        for (person, table) in settings.fixed_tables() {
+            assert_eq!(table_of(&assignment, &person), table);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_absent() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.set_absent("Harry Potter", true);
+
+        let assignment = solve(&tables, &tribe, SolverSettings::default())?;
+        assert_eq!(
+            assignment.values().flatten().count(),
+            tribe.persons_count() - 1
+        );
+        assert!(!assignment.values().flatten().any(|p| p == "Harry Potter"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_rsvp() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.set_rsvp("Harry Potter", model::Rsvp::Tentative);
+        tribe.set_rsvp("Ron Weasley", model::Rsvp::Declined);
+        let seated = |assignment: &model::Assignment, person: &str| {
+            assignment.values().flatten().any(|p| p == person)
+        };
+
+        let assignment = solve(&tables, &tribe, SolverSettings::default())?;
+        assert!(seated(&assignment, "Harry Potter"));
+        assert!(!seated(&assignment, "Ron Weasley"));
+
+        let settings = SolverSettings {
+            include_tentative: false,
+            ..Default::default()
+        };
+        let assignment = solve(&tables, &tribe, settings)?;
+        assert!(!seated(&assignment, "Harry Potter"));
+        assert!(seated(&assignment, "Hermione Granger"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_monitored() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let settings = SolverSettings {
+            rounds: 3,
+            starts: 16,
+            ..Default::default()
+        };
+
+        let mut reports = Vec::new();
+        let rounds = {
+            let mut monitor =
+                Monitor::new().with_progress(Duration::ZERO, |p| reports.push(p.clone()));
+            block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings.clone(),
+                &[],
+                &mut monitor,
+            ))?
+            .rounds
+        };
+        assert_eq!(rounds.len(), 3);
+        assert_eq!(reports.last().map(|p| p.round), Some(2));
+        assert!(reports.windows(2).all(|w| w[0].iteration <= w[1].iteration));
+        // Later starts do not bring back a worse objective
+        assert!(
+            reports
+                .windows(2)
+                .filter(|w| w[0].round == w[1].round)
+                .all(|w| w[0].objective >= w[1].objective)
+        );
+
+        // Cancelling returns the best assignment so far
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let mut monitor = Monitor::new().with_cancel(cancel);
+        let rounds = block_on(resolve_rounds_monitored(
+            &tables,
+            &tribe,
+            settings,
+            &[],
+            &mut monitor,
+        ))?
+        .rounds;
+        assert_eq!(rounds.len(), 1);
+        assert_eq!(rounds[0].values().flatten().count(), tribe.persons_count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_cancel_from_progress() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let budget = Duration::from_secs(60);
+        let settings = SolverSettings {
+            rounds: 2,
+            time_budget: Some(budget),
+            ..Default::default()
+        };
+
+        // Stop partway through the first round, as done by a stop button
+        let cancel = CancelToken::new();
+        let mut reports = Vec::new();
+        let output = {
+            let token = cancel.clone();
+            let mut monitor =
+                Monitor::new()
+                    .with_cancel(cancel.clone())
+                    .with_progress(Duration::ZERO, |p| {
+                        reports.push(p.clone());
+                        if p.iteration >= 1000 {
+                            token.cancel();
+                        }
+                    });
+            block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings,
+                &[],
+                &mut monitor,
+            ))?
+        };
+        assert!(cancel.is_cancelled());
+        assert!(output.stats.elapsed < budget);
+        assert!(output.stats.iterations < 10_000);
+        assert!(reports.iter().all(|p| p.round == 0));
+        assert_eq!(output.rounds.len(), 1);
+        assert_eq!(
+            output.rounds[0].values().flatten().count(),
+            tribe.persons_count()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_budget_single_table() -> SolverResult<()> {
+        let mut tribe = model::Tribe::new();
+        for p in ["A", "B", "C"] {
+            tribe.add_person(p);
+        }
+        let tables = model::Tables::from_iter([("1".to_owned(), model::TableType { n_seats: 4 })]);
+        let settings = SolverSettings {
+            iteration_budget: Some(100),
+            ..Default::default()
+        };
+
+        // Nobody can move, which must not keep the search waiting for iterations
+        let rounds = solve_rounds(&tables, &tribe, settings)?;
+        assert_eq!(rounds[0]["1"].len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_time_budget() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let solve_with = |time_budget| -> SolverResult<_> {
+            let settings = SolverSettings {
+                time_budget,
+                ..Default::default()
+            };
+            let output = block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings.clone(),
+                &[],
+                &mut Monitor::new(),
+            ))?;
+            let cost = score(&tables, &tribe, settings, &output.rounds[0])?.total_cost;
+            Ok((output, cost))
+        };
+
+        let budget = Duration::from_millis(200);
+        let (quick, quick_cost) = solve_with(None)?;
+        let (output, cost) = solve_with(Some(budget))?;
+        assert!(output.stats.elapsed >= budget);
+        assert!(output.stats.iterations > quick.stats.iterations);
+        assert_eq!(
+            output.rounds[0].values().flatten().count(),
+            tribe.persons_count()
+        );
+        assert!(cost <= quick_cost + 1e-4);
+
+        // Also without a monitor
+        let settings = SolverSettings {
+            time_budget: Some(budget),
+            ..Default::default()
+        };
+        let stopwatch = Stopwatch::start();
+        solve(&tables, &tribe, settings)?;
+        assert!(stopwatch.elapsed() >= budget);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_seed() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let solve_with = |seed| {
+            let settings = SolverSettings {
+                rounds: 2,
+                iteration_budget: Some(20_000),
+                seed,
+                ..Default::default()
+            };
+            block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings,
+                &[],
+                &mut Monitor::new(),
+            ))
+        };
+
+        let output = solve_with(7)?;
+        assert_eq!(output.stats.seed, 7);
+        assert!(output.stats.iterations >= 20_000);
+        assert_eq!(output.rounds, solve_with(7)?.rounds);
+        // Other seeds may or may not find the same assignments, but still solve
+        assert_eq!(solve_with(8)?.rounds.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_multi_start() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        let tribe_order = solver.seating_blocks(SeatingOrder::Tribe);
+        let constrained = solver.seating_blocks(SeatingOrder::MostConstrained);
+        let random = solver.seating_blocks(solver.seating_order(2));
+        assert_eq!(constrained.len(), tribe_order.len());
+        assert_eq!(random.len(), tribe_order.len());
+        // A random order is reproducible for the same seed and start, and differs otherwise
+        let persons =
+            |blocks: &[SeatingBlock]| blocks.iter().map(|b| b.persons.clone()).collect::<Vec<_>>();
+        assert_ne!(persons(&random), persons(&tribe_order));
+        assert_eq!(
+            persons(&random),
+            persons(&solver.seating_blocks(solver.seating_order(2)))
+        );
+        assert_ne!(
+            persons(&random),
+            persons(&solver.seating_blocks(solver.seating_order(3)))
+        );
+        let other_seed = Solver::new(
+            &tables,
+            &tribe,
+            SolverSettings {
+                seed: 1,
+                ..Default::default()
+            },
+        )?;
+        assert_ne!(
+            persons(&random),
+            persons(&other_seed.seating_blocks(other_seed.seating_order(2)))
+        );
+        // The most constrained person without a group comes first among them
+        let relations: &RelationGraph = solver.assignor.as_ref();
+        let alone = constrained
+            .iter()
+            .filter(|b| solver.assignor.person_group(b.persons[0]).is_none())
+            .map(|b| relations.neighbors_slice(b.persons[0]).len())
+            .collect::<Vec<_>>();
+        assert!(alone.is_sorted_by(|a, b| a >= b));
+
+        let total_cost = |starts| -> SolverResult<Cost> {
+            let settings = SolverSettings {
+                starts,
+                ..Default::default()
+            };
+            let assignment = solve(&tables, &tribe, settings.clone())?;
+            assert_eq!(assignment.values().flatten().count(), tribe.persons_count());
+            Ok(score(&tables, &tribe, settings, &assignment)?.total_cost)
+        };
+        // The first start is the single start solve
+        assert!(total_cost(8)? <= total_cost(1)? + 1e-4);
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_solver_parallel_starts() -> SolverResult<()> {
+        let (tribe, tables) = examples::random(60, 8, 1);
+        let solve_with = |threads, iteration_budget| -> SolverResult<_> {
+            let settings = SolverSettings {
+                starts: 8,
+                threads,
+                iteration_budget,
+                ..Default::default()
+            };
+            let mut reports = Vec::new();
+            let mut monitor =
+                Monitor::new().with_progress(Duration::ZERO, |p| reports.push(p.iteration));
+            let output = block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings,
+                &[],
+                &mut monitor,
+            ))?;
+            drop(monitor);
+            Ok((output, reports))
+        };
+
+        let (sequential, _) = solve_with(1, None)?;
+        let (parallel, reports) = solve_with(4, None)?;
+        assert_eq!(parallel.rounds, sequential.rounds);
+        assert_eq!(parallel.stats.iterations, sequential.stats.iterations);
+        // Workers report their progress while running
+        assert!(reports.len() > 1);
+        assert!(reports.is_sorted());
+
+        // Starts share the budget the same way on any number of threads
+        let budget = Some(20_000);
+        let (sequential, _) = solve_with(1, budget)?;
+        let (parallel, _) = solve_with(4, budget)?;
+        assert_eq!(parallel.rounds, sequential.rounds);
+        assert_eq!(parallel.stats.iterations, sequential.stats.iterations);
+        assert!(parallel.stats.iterations >= 20_000);
+
+        Ok(())
+    }
+
+    /// Compare running starts on one thread and on all cores.
+    ///
+    /// Run with `cargo test --release --features parallel bench_parallel_starts -- --ignored
+    /// --nocapture`.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    #[test]
+    #[ignore = "benchmark, slow outside of release builds"]
+    fn bench_parallel_starts() -> SolverResult<()> {
+        const STARTS: u32 = 32;
+
+        let (tribe, tables) = examples::random(200, 10, 0);
+        let solve_with = |threads| -> SolverResult<SolverOutput> {
+            let settings = SolverSettings {
+                starts: STARTS,
+                threads,
+                ..Default::default()
+            };
+            block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings,
+                &[],
+                &mut Monitor::new(),
+            ))
+        };
+
+        let sequential = solve_with(1)?;
+        let parallel = solve_with(0)?;
+        assert_eq!(parallel.rounds, sequential.rounds);
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        println!(
+            "{STARTS} starts: {:.2?} on 1 thread, {:.2?} on {threads} threads, {:.1}x speedup",
+            sequential.stats.elapsed,
+            parallel.stats.elapsed,
+            sequential.stats.elapsed.as_secs_f64() / parallel.stats.elapsed.as_secs_f64(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_alternatives() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        // Also searching from the best start, which finds more alternatives
+        for iteration_budget in [None, Some(20_000)] {
+            let settings = SolverSettings {
+                alternatives: 3,
+                alternative_min_moved: 4,
+                rounds: 2,
+                starts: 8,
+                iteration_budget,
+                ..Default::default()
+            };
+            let output = block_on(resolve_rounds_monitored(
+                &tables,
+                &tribe,
+                settings,
+                &[],
+                &mut Monitor::new(),
+            ))?;
+
+            assert_eq!(output.alternatives.len(), 2);
+            for (round, alternatives) in output.rounds.iter().zip(output.alternatives.iter()) {
+                assert!(alternatives.len() >= 2 && alternatives.len() <= 3);
+                assert_eq!(&alternatives[0].assignment, round);
+                assert!(alternatives.is_sorted_by(|a1, a2| a1.cost <= a2.cost));
+                for (i, a1) in alternatives.iter().enumerate() {
+                    for a2 in alternatives.iter().skip(i + 1) {
+                        let moved = model::moved_persons(&a1.assignment, &a2.assignment);
+                        assert!(moved.len() >= 4);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_lower_bound() -> SolverResult<()> {
+        // Three persons who love each other but only two can sit together
+        let mut tribe = model::Tribe::new();
+        tribe.add_relation("Harry", "Ginny", RelationStrength::Loves);
+        tribe.add_relation("Ginny", "Luna", RelationStrength::Loves);
+        tribe.add_relation("Luna", "Harry", RelationStrength::Loves);
+        let tables = model::Tables::from_iter([
+            ("Phoenix".to_owned(), model::TableType { n_seats: 2 }),
+            ("Niffler".to_owned(), model::TableType { n_seats: 2 }),
+        ]);
+        let settings = SolverSettings {
+            fill_balance_value: 0.0,
+            ..Default::default()
+        };
+        let output = block_on(resolve_rounds_monitored(
+            &tables,
+            &tribe,
+            settings,
+            &[],
+            &mut Monitor::new(),
+        ))?;
+        let score = &output.scores[0];
+        assert_eq!(score.total_cost, -4.0);
+        assert_eq!(score.lower_bound, -6.0);
+        assert_eq!(score.gap(), 2.0);
+
+        // The bound holds with all kinds of costs
+        let (mut tribe, tables) = examples::harry_potter();
+        for (p, house) in [
+            ("Harry Potter", "Gryffindor"),
+            ("Luna Lovegood", "Ravenclaw"),
+            ("Hannah Abbott", "Hufflepuff"),
+        ] {
+            tribe.add_attribute(p, model::Attribute::new("House", house));
+        }
+        let settings = SolverSettings {
+            attribute_goals: vec![model::AttributeGoal::Mix {
+                name: "House".into(),
+            }],
+            rounds: 2,
+            repeat_pair_value: 1.0,
+            ..Default::default()
+        };
+        let output = block_on(resolve_rounds_monitored(
+            &tables,
+            &tribe,
+            settings,
+            &[],
+            &mut Monitor::new(),
+        ))?;
+        for score in output.scores.iter() {
+            assert!(score.lower_bound <= score.total_cost);
+            assert_eq!(score.gap(), score.total_cost - score.lower_bound);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_infeasibility() -> SolverResult<()> {
+        let (mut tribe, tables) = examples::harry_potter();
+        tribe.add_group("Everyone", None, model::GroupCohesion::Require);
+        let persons = tribe.persons().cloned().collect::<Vec<_>>();
+        for p in persons.iter() {
+            tribe.add_to_group("Everyone", p);
+        }
+        let mut settings = SolverSettings::default();
+        for (p, table) in persons.iter().zip(["Niffler", "Willow"]) {
+            settings.pins.insert(p.clone(), table.into());
+        }
+
+        let Err(SolverError::NoSolution(reasons)) = solve(&tables, &tribe, settings.clone()) else {
+            panic!("the problem should be infeasible");
+        };
+        // All reasons are reported at once
+        assert_eq!(reasons.len(), 3, "{reasons:?}");
+        assert!(reasons.contains(&Infeasibility::GroupTooLarge {
+            group: "Everyone".into(),
+            members: persons.len(),
+            largest_table: 12,
+        }));
+        assert!(reasons.contains(&Infeasibility::GroupPinnedApart {
+            group: "Everyone".into(),
+            tables: vec!["Niffler".into(), "Willow".into()],
+        }));
+        assert!(reasons.iter().all(|r| matches!(
+            r,
+            Infeasibility::GroupTooLarge { .. }
+                | Infeasibility::GroupPinnedApart { .. }
+                | Infeasibility::TableOverfilledByPins { seats: 6, .. }
+        )));
+
+        settings.min_table_persons = 7;
+        let Err(SolverError::NoSolution(reasons)) = solve(&tables, &tribe, settings) else {
+            panic!("the problem should be infeasible");
+        };
+        assert!(reasons.contains(&Infeasibility::TableBelowMinimum {
+            table: "Niffler".into(),
+            seats: 6,
+            min_persons: 7,
+        }));
+        // Along with those not about tables
+        assert!(
+            reasons
+                .iter()
+                .any(|r| matches!(r, Infeasibility::GroupTooLarge { .. }))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_improve_total() -> SolverResult<()> {
+        let (tribe, tables) = examples::harry_potter();
+        let mut solver = Solver::new(&tables, &tribe, SolverSettings::default())?;
+        block_on(solver.seat_greedily(SeatingOrder::Tribe, &mut Monitor::new()))?;
+        let greedy = solver.objective_value();
+        block_on(solver.improve(&mut Monitor::new()));
+        let improved = solver.objective_value();
+        // The local search changes the result of the default objective
+        assert!(improved.improves_on(&greedy));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_closed_tables() {
+        let (_, tables) = examples::harry_potter();
+        let tribe = tribe_of(10);
+
+        let settings = SolverSettings {
+            close_unused_tables: true,
+            ..Default::default()
+        };
+        let closed = closed_tables(&tables, &tribe, &settings);
+        assert_eq!(
+            closed,
+            BTreeSet::from(["Moonstones".into(), "Niffler".into(), "Willow".into()])
+        );
+
+        // Tables left empty are not closed
+        let settings = SolverSettings {
+            min_table_persons: 0,
+            ..Default::default()
+        };
+        assert!(closed_tables(&tables, &tribe, &settings).is_empty());
+    }
 }
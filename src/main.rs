@@ -1,4 +1,6 @@
 pub mod app;
+pub mod background;
+pub mod history;
 pub mod logic;
 pub mod name_generator;
 
@@ -7,9 +9,11 @@ use dioxus::prelude::*;
 use app::NotFound;
 use app::problem::Page as ProblemPage;
 use app::solution::Page as SolutionPage;
+use history::History;
 use logic::{
     model::{Assignment, Tables, Tribe},
-    solver::SolverError,
+    monitor::{CancelToken, Progress},
+    solver::{Alternative, SolveStats, SolverError, SolverSettings},
 };
 
 const FAVICON: &str = concat!(
@@ -24,26 +28,139 @@ const MAIN_CSS: Asset = asset!("/assets/main.css");
 struct ProblemSignal {
     pub tables: Signal<Tables>,
     pub tribe: Signal<Tribe>,
+    pub settings: Signal<SolverSettings>,
+    pub history: ProblemHistory,
 }
 
 impl ProblemSignal {
     pub fn new() -> Self {
+        let tables = Signal::new(Tables::new());
+        let tribe = Signal::new(Tribe::new());
+        let settings = Signal::new(SolverSettings::default());
         Self {
-            tables: Signal::new(Tables::new()),
-            tribe: Signal::new(Tribe::new()),
+            tables,
+            tribe,
+            settings,
+            history: ProblemHistory {
+                tables,
+                tribe,
+                settings,
+                edits: Signal::new(History::default()),
+            },
+        }
+    }
+}
+
+/// The parts of the problem as they were before an edit, or after it once undone.
+#[derive(Clone, Default)]
+struct ProblemEdit {
+    tables: Option<Tables>,
+    tribe: Option<Tribe>,
+    settings: Option<SolverSettings>,
+}
+
+/// Edit the problem while recording how to undo the edits.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ProblemHistory {
+    tables: Signal<Tables>,
+    tribe: Signal<Tribe>,
+    settings: Signal<SolverSettings>,
+    edits: Signal<History<ProblemEdit>>,
+}
+
+impl ProblemHistory {
+    pub fn edit_tables(&mut self, edit: impl FnOnce(&mut Tables)) {
+        self.edits.write().record(ProblemEdit {
+            tables: Some(self.tables.peek().clone()),
+            ..Default::default()
+        });
+        edit(&mut self.tables.write());
+    }
+
+    pub fn edit_tribe(&mut self, edit: impl FnOnce(&mut Tribe)) {
+        self.edits.write().record(ProblemEdit {
+            tribe: Some(self.tribe.peek().clone()),
+            ..Default::default()
+        });
+        edit(&mut self.tribe.write());
+    }
+
+    pub fn edit_settings(&mut self, edit: impl FnOnce(&mut SolverSettings)) {
+        self.edits.write().record(ProblemEdit {
+            settings: Some(self.settings.peek().clone()),
+            ..Default::default()
+        });
+        edit(&mut self.settings.write());
+    }
+
+    /// Replace both the tables and the tribe as a single edit.
+    pub fn replace(&mut self, tables: Tables, tribe: Tribe) {
+        self.edits.write().record(ProblemEdit {
+            tables: Some(self.tables.replace(tables)),
+            tribe: Some(self.tribe.replace(tribe)),
+            ..Default::default()
+        });
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.edits.read().can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.edits.read().can_redo()
+    }
+
+    pub fn undo(&mut self) {
+        let mut this = *self;
+        self.edits.write().undo(|edit| this.swap(edit));
+    }
+
+    pub fn redo(&mut self) {
+        let mut this = *self;
+        self.edits.write().redo(|edit| this.swap(edit));
+    }
+
+    /// Exchange the current problem parts with the ones in the edit.
+    fn swap(&mut self, edit: &mut ProblemEdit) {
+        if let Some(tables) = &mut edit.tables {
+            std::mem::swap(tables, &mut *self.tables.write());
+        }
+        if let Some(tribe) = &mut edit.tribe {
+            std::mem::swap(tribe, &mut *self.tribe.write());
+        }
+        if let Some(settings) = &mut edit.settings {
+            std::mem::swap(settings, &mut *self.settings.write());
         }
     }
 }
 
 #[derive(Clone, PartialEq, Eq)]
 struct SolutionSignal {
+    /// The assignment currently displayed, one of the rounds.
     pub assignment: Signal<Assignment>,
     pub state: Signal<SolutionState>,
+    pub rounds: Signal<Vec<Assignment>>,
+    pub round: Signal<usize>,
+    /// Rounds before the last minimal re-solve, to show who moved.
+    pub previous_rounds: Signal<Vec<Assignment>>,
+    /// The last progress of the running solve.
+    pub progress: Signal<Option<Progress>>,
+    /// Stops the running solve, only set while one is running.
+    pub cancel: Signal<Option<CancelToken>>,
+    /// How the last solve went.
+    pub stats: Signal<Option<SolveStats>>,
+    /// Best assignments of every round to choose from.
+    pub alternatives: Signal<Vec<Vec<Alternative>>>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
 enum SolutionState {
     Missing,
+    /// A solve is running and the problem did not change since it started.
+    ///
+    /// Whether a solve is running is told by [`SolutionSignal::cancel`], since the problem may
+    /// change meanwhile, making the solution outdated.
+    Solving,
     Outdated,
     Valid,
     Error(SolverError),
@@ -54,6 +171,51 @@ impl SolutionSignal {
         Self {
             assignment: Signal::new(Assignment::new()),
             state: Signal::new(SolutionState::Missing),
+            rounds: Signal::new(Vec::new()),
+            round: Signal::new(0),
+            previous_rounds: Signal::new(Vec::new()),
+            progress: Signal::new(None),
+            cancel: Signal::new(None),
+            stats: Signal::new(None),
+            alternatives: Signal::new(Vec::new()),
+        }
+    }
+
+    /// Replace all rounds, displaying the first one.
+    pub fn set_rounds(&mut self, rounds: Vec<Assignment>) {
+        self.assignment
+            .set(rounds.first().cloned().unwrap_or_default());
+        self.rounds.set(rounds);
+        self.round.set(0);
+        self.previous_rounds.set(Vec::new());
+    }
+
+    /// Replace all rounds with ones derived from the current ones, to show who moved.
+    pub fn update_rounds(&mut self, rounds: Vec<Assignment>) {
+        let previous = self.current_rounds();
+        self.set_rounds(rounds);
+        self.previous_rounds.set(previous);
+    }
+
+    /// All rounds, including changes made to the displayed one.
+    pub fn current_rounds(&self) -> Vec<Assignment> {
+        let mut rounds = self.rounds.peek().clone();
+        if let Some(current) = rounds.get_mut(*self.round.peek()) {
+            *current = self.assignment.peek().clone();
+        }
+        rounds
+    }
+
+    /// Display another round, keeping changes made to the current one.
+    pub fn select_round(&mut self, round: usize) {
+        let current = self.assignment.peek().clone();
+        let mut rounds = self.rounds.write();
+        if let Some(previous) = rounds.get_mut(*self.round.peek()) {
+            *previous = current;
+        }
+        if let Some(next) = rounds.get(round) {
+            self.assignment.set(next.clone());
+            self.round.set(round);
         }
     }
 }
@@ -90,11 +252,36 @@ fn App() -> Element {
     use_effect(move || {
         let _r1 = &pb.tribe.read();
         let _r2 = &pb.tables.read();
+        let _r3 = &pb.settings.read();
         if *sol.state.peek() != SolutionState::Missing {
             sol.state.set(SolutionState::Outdated);
         }
     });
 
+    // Undo and redo shortcuts, leaving text fields to their own history
+    let mut history = pb.history;
+    use_future(move || async move {
+        let mut shortcuts = document::eval(
+            r#"
+            document.addEventListener("keydown", (event) => {
+                const target = event.target.tagName;
+                if (target === "INPUT" || target === "TEXTAREA") return;
+                if ((event.ctrlKey || event.metaKey) && event.key.toLowerCase() === "z") {
+                    event.preventDefault();
+                    dioxus.send(event.shiftKey ? "redo" : "undo");
+                }
+            });
+            "#,
+        );
+        while let Ok(action) = shortcuts.recv::<String>().await {
+            match action.as_str() {
+                "undo" => history.undo(),
+                "redo" => history.redo(),
+                _ => {}
+            }
+        }
+    });
+
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
         document::Link { rel: "stylesheet", href: MAIN_CSS }
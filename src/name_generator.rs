@@ -1,4 +1,103 @@
+use crate::logic::model::Tables;
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString,
+)]
+pub enum Theme {
+    #[default]
+    Numbers,
+    Letters,
+    Flowers,
+    Cities,
+}
+
+impl Theme {
+    pub fn iter() -> impl Clone + DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+        <Self as strum::IntoEnumIterator>::iter()
+    }
+
+    /// The base vocabulary of themes that have one, cycled with a numbered suffix when exhausted.
+    fn words(&self) -> Option<&'static [&'static str]> {
+        const FLOWERS: [&str; 16] = [
+            "Rose",
+            "Tulip",
+            "Lily",
+            "Daisy",
+            "Orchid",
+            "Peony",
+            "Iris",
+            "Lavender",
+            "Magnolia",
+            "Dahlia",
+            "Jasmine",
+            "Poppy",
+            "Violet",
+            "Camellia",
+            "Sunflower",
+            "Lotus",
+        ];
+        const CITIES: [&str; 16] = [
+            "Paris",
+            "Tokyo",
+            "Lisbon",
+            "Montréal",
+            "Kyoto",
+            "Cairo",
+            "Lima",
+            "Oslo",
+            "Berlin",
+            "Dakar",
+            "Hanoi",
+            "Quito",
+            "Vienna",
+            "Sydney",
+            "Prague",
+            "Rome",
+        ];
+
+        match self {
+            Theme::Numbers | Theme::Letters => None,
+            Theme::Flowers => Some(&FLOWERS),
+            Theme::Cities => Some(&CITIES),
+        }
+    }
+
+    /// The name at the given zero-based position in the theme sequence.
+    fn name(&self, idx: usize) -> String {
+        match self {
+            Theme::Numbers => (idx + 1).to_string(),
+            Theme::Letters => letters(idx),
+            Theme::Flowers | Theme::Cities => {
+                let words = self.words().unwrap();
+                let word = words[idx % words.len()];
+                match idx / words.len() {
+                    0 => word.to_owned(),
+                    round => format!("{} {}", word, round + 1),
+                }
+            }
+        }
+    }
+}
+
+/// Spreadsheet-like column names: A, B, ..., Z, AA, AB, ...
+fn letters(mut idx: usize) -> String {
+    const BASE: usize = 26;
+    let mut out = Vec::new();
+    loop {
+        out.push(b'A' + (idx % BASE) as u8);
+        if idx < BASE {
+            break;
+        }
+        idx = idx / BASE - 1;
+    }
+    out.reverse();
+    // Safe because only ASCII letters were pushed
+    String::from_utf8(out).unwrap()
+}
+
+#[derive(Clone, Debug)]
 pub struct NameGenerator {
+    theme: Theme,
     current: usize,
 }
 
@@ -10,7 +109,26 @@ impl Default for NameGenerator {
 
 impl NameGenerator {
     pub fn new() -> Self {
-        Self { current: 1 }
+        Self::with_theme(Theme::default())
+    }
+
+    pub fn with_theme(theme: Theme) -> Self {
+        Self { theme, current: 0 }
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Generate the next name that is not already a table name.
+    pub fn next_free(&mut self, tables: &Tables) -> String {
+        // Safe because the generator never ends
+        self.find(|name| !tables.contains_key(name)).unwrap()
+    }
+
+    /// The name [`Self::next_free`] would return, without advancing the generator.
+    pub fn peek_free(&self, tables: &Tables) -> String {
+        self.clone().next_free(tables)
     }
 }
 
@@ -18,7 +136,7 @@ impl Iterator for NameGenerator {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let out = Some(self.current.to_string());
+        let out = Some(self.theme.name(self.current));
         self.current += 1;
         out
     }
@@ -29,14 +147,43 @@ mod tests {
     use std::collections::HashSet;
 
     use super::*;
+    use crate::logic::model::TableType;
 
     #[test]
     fn test_name_generator() {
-        let mut generator = NameGenerator::new();
-        assert!(generator.next().is_some());
+        for theme in Theme::iter() {
+            let mut generator = NameGenerator::with_theme(theme);
+            assert!(generator.next().is_some());
+
+            const CNT: usize = 10_000;
+            let set = generator.take(CNT).collect::<HashSet<_>>();
+            assert_eq!(set.len(), CNT);
+        }
+    }
+
+    #[test]
+    fn test_name_generator_themes() {
+        let take = |theme, n| NameGenerator::with_theme(theme).take(n).collect::<Vec<_>>();
+
+        assert_eq!(take(Theme::Numbers, 3), vec!["1", "2", "3"]);
+        assert_eq!(take(Theme::Letters, 3), vec!["A", "B", "C"]);
+        assert_eq!(take(Theme::Letters, 28)[25..], ["Z", "AA", "AB"]);
+        assert_eq!(letters(26 + 26 * 26), "AAA");
+        assert_eq!(take(Theme::Flowers, 2), vec!["Rose", "Tulip"]);
+        assert_eq!(take(Theme::Flowers, 17)[16], "Rose 2");
+    }
+
+    #[test]
+    fn test_name_generator_free() {
+        let tables = Tables::from_iter([
+            ("1".to_owned(), TableType { n_seats: 4 }),
+            ("3".to_owned(), TableType { n_seats: 4 }),
+        ]);
 
-        const CNT: usize = 10_000;
-        let set = generator.take(CNT).collect::<HashSet<_>>();
-        assert_eq!(set.len(), CNT);
+        let mut generator = NameGenerator::new();
+        assert_eq!(generator.peek_free(&tables), "2");
+        assert_eq!(generator.peek_free(&tables), "2");
+        assert_eq!(generator.next_free(&tables), "2");
+        assert_eq!(generator.next_free(&tables), "4");
     }
 }